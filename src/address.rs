@@ -0,0 +1,222 @@
+use std::{
+    fmt::Display,
+    net::{IpAddr, SocketAddr, ToSocketAddrs},
+};
+
+use crate::{ConfigError, FlatValue, TryUnwrap};
+
+/// ## Host and port
+///
+/// A `host:port` pair where the host may be a domain name or an IP address (IPv6 addresses are
+/// written between brackets like `[::1]:8080`). Unlike [SocketAddr], the host is not resolved.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HostPort {
+    pub host: String,
+    pub port: u16,
+}
+
+impl HostPort {
+    pub fn new(host: &str, port: u16) -> Self {
+        Self {
+            host: host.to_string(),
+            port,
+        }
+    }
+
+    /// Parse a `host:port` text, the given default port is used when none is specified.
+    pub fn parse(text: &str, default_port: Option<u16>) -> Result<Self, ConfigError> {
+        let error = |reason: &str| {
+            ConfigError::IncorrectValue(format!("invalid host and port '{text}', {reason}"))
+        };
+        let trimmed = text.trim();
+        let (host, port) = if let Some(rest) = trimmed.strip_prefix('[') {
+            let (host, rest) = rest
+                .split_once(']')
+                .ok_or_else(|| error("missing closing bracket"))?;
+            let port = match rest {
+                "" => None,
+                rest => Some(
+                    rest.strip_prefix(':')
+                        .ok_or_else(|| error("unexpected text after the address"))?,
+                ),
+            };
+
+            (host, port)
+        } else if trimmed.matches(':').count() > 1 {
+            // Bare IPv6 address, it cannot hold a port.
+            (trimmed, None)
+        } else {
+            match trimmed.split_once(':') {
+                Some((host, port)) => (host, Some(port)),
+                None => (trimmed, None),
+            }
+        };
+
+        if host.is_empty() {
+            return Err(error("the host is empty"));
+        }
+
+        if host.chars().any(|c| c.is_whitespace() || c == '/') {
+            return Err(error("the host contains invalid characters"));
+        }
+
+        let port = match port {
+            Some(port) => port
+                .parse()
+                .map_err(|_| error("the port must be a number between 0 and 65535"))?,
+            None => default_port.ok_or_else(|| error("the port is missing"))?,
+        };
+
+        Ok(Self::new(host, port))
+    }
+}
+
+impl Display for HostPort {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.host.contains(':') {
+            write!(f, "[{}]:{}", self.host, self.port)
+        } else {
+            write!(f, "{}:{}", self.host, self.port)
+        }
+    }
+}
+
+impl ToSocketAddrs for HostPort {
+    type Iter = std::vec::IntoIter<SocketAddr>;
+
+    fn to_socket_addrs(&self) -> std::io::Result<Self::Iter> {
+        (self.host.as_str(), self.port).to_socket_addrs()
+    }
+}
+
+fn expect_text<'a>(value: &'a FlatValue, expected: &str) -> Result<&'a str, ConfigError> {
+    match value {
+        FlatValue::Text(t) => Ok(t),
+        _ => Err(ConfigError::TypeMismatch {
+            expected: expected.to_string(),
+            present: value.display(),
+        }),
+    }
+}
+
+impl FlatValue {
+    /// Convert the value as a [SocketAddr], the given default port is used when the value is a
+    /// bare IP address.
+    pub fn try_unwrap_socket_addr(&self, default_port: u16) -> Result<SocketAddr, ConfigError> {
+        let text = expect_text(self, "socket address")?;
+        let host_port = HostPort::parse(text, Some(default_port))?;
+        let ip: IpAddr = host_port.host.parse().map_err(|_| {
+            ConfigError::IncorrectValue(format!(
+                "invalid socket address '{text}', '{}' is not an IP address",
+                host_port.host
+            ))
+        })?;
+
+        Ok(SocketAddr::new(ip, host_port.port))
+    }
+
+    /// Convert the value as a [HostPort], the given default port is used when none is specified.
+    pub fn try_unwrap_host_port(&self, default_port: u16) -> Result<HostPort, ConfigError> {
+        HostPort::parse(expect_text(self, "host and port")?, Some(default_port))
+    }
+}
+
+impl TryUnwrap<IpAddr> for FlatValue {
+    fn try_unwrap(&self) -> Result<IpAddr, ConfigError> {
+        let text = expect_text(self, "IP address")?;
+
+        text.trim()
+            .parse()
+            .map_err(|_| ConfigError::IncorrectValue(format!("invalid IP address '{text}'")))
+    }
+}
+
+/// The port is mandatory, use [FlatValue::try_unwrap_socket_addr] to provide a default port.
+impl TryUnwrap<SocketAddr> for FlatValue {
+    fn try_unwrap(&self) -> Result<SocketAddr, ConfigError> {
+        let text = expect_text(self, "socket address")?;
+
+        text.trim().parse().map_err(|_| {
+            ConfigError::IncorrectValue(format!(
+                "invalid socket address '{text}', expected 'ip:port'"
+            ))
+        })
+    }
+}
+
+/// The port is mandatory, use [FlatValue::try_unwrap_host_port] to provide a default port.
+impl TryUnwrap<HostPort> for FlatValue {
+    fn try_unwrap(&self) -> Result<HostPort, ConfigError> {
+        HostPort::parse(expect_text(self, "host and port")?, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    use super::*;
+
+    #[test]
+    fn ip_addr() {
+        let ip: IpAddr = FlatValue::from("127.0.0.1").try_unwrap().unwrap();
+        assert_eq!(IpAddr::V4(Ipv4Addr::LOCALHOST), ip);
+
+        let ip: IpAddr = FlatValue::from("::1").try_unwrap().unwrap();
+        assert_eq!(IpAddr::V6(Ipv6Addr::LOCALHOST), ip);
+
+        let result: Result<IpAddr, ConfigError> = FlatValue::from("localhost").try_unwrap();
+        assert!(matches!(result, Err(ConfigError::IncorrectValue(_))));
+
+        let result: Result<IpAddr, ConfigError> = FlatValue::Integer(1).try_unwrap();
+        assert!(matches!(result, Err(ConfigError::TypeMismatch { .. })));
+    }
+
+    #[test]
+    fn socket_addr() {
+        let addr: SocketAddr = FlatValue::from("0.0.0.0:8080").try_unwrap().unwrap();
+        assert_eq!(8080, addr.port());
+
+        let result: Result<SocketAddr, ConfigError> = FlatValue::from("0.0.0.0").try_unwrap();
+        assert!(result.is_err());
+
+        let addr = FlatValue::from("10.0.0.1")
+            .try_unwrap_socket_addr(443)
+            .unwrap();
+        assert_eq!("10.0.0.1:443".parse::<SocketAddr>().unwrap(), addr);
+
+        let addr = FlatValue::from("[::1]")
+            .try_unwrap_socket_addr(443)
+            .unwrap();
+        assert_eq!("[::1]:443".parse::<SocketAddr>().unwrap(), addr);
+
+        let addr = FlatValue::from("::1").try_unwrap_socket_addr(443).unwrap();
+        assert_eq!("[::1]:443".parse::<SocketAddr>().unwrap(), addr);
+
+        assert!(FlatValue::from("example.com:80")
+            .try_unwrap_socket_addr(443)
+            .is_err());
+    }
+
+    #[test]
+    fn host_port() {
+        let host_port: HostPort = FlatValue::from("example.com:5432").try_unwrap().unwrap();
+        assert_eq!(HostPort::new("example.com", 5432), host_port);
+        assert_eq!("example.com:5432", host_port.to_string());
+
+        let host_port = FlatValue::from("db.local")
+            .try_unwrap_host_port(5432)
+            .unwrap();
+        assert_eq!(HostPort::new("db.local", 5432), host_port);
+
+        let host_port: HostPort = FlatValue::from("[fe80::1]:53").try_unwrap().unwrap();
+        assert_eq!(HostPort::new("fe80::1", 53), host_port);
+        assert_eq!("[fe80::1]:53", host_port.to_string());
+
+        let result: Result<HostPort, ConfigError> = FlatValue::from("db.local").try_unwrap();
+        assert!(result.is_err());
+        assert!(HostPort::parse("db.local:99999", None).is_err());
+        assert!(HostPort::parse(":80", None).is_err());
+        assert!(HostPort::parse("[::1", Some(80)).is_err());
+    }
+}
@@ -0,0 +1,316 @@
+use std::fmt::Display;
+
+use crate::{ConfigError, FlatValue, TryUnwrap};
+
+const SI_UNITS: [&str; 7] = ["B", "KB", "MB", "GB", "TB", "PB", "EB"];
+const IEC_UNITS: [&str; 7] = ["B", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+
+/// ## Byte unit system
+///
+/// SI units are powers of 1000 (`KB`, `MB`, `GB`…) whereas IEC units are powers of 1024 (`KiB`,
+/// `MiB`, `GiB`…).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ByteUnitSystem {
+    Si,
+    #[default]
+    Iec,
+}
+
+impl ByteUnitSystem {
+    fn base(&self) -> u64 {
+        match self {
+            Self::Si => 1_000,
+            Self::Iec => 1_024,
+        }
+    }
+
+    fn units(&self) -> &'static [&'static str; 7] {
+        match self {
+            Self::Si => &SI_UNITS,
+            Self::Iec => &IEC_UNITS,
+        }
+    }
+}
+
+/// ## Byte size
+///
+/// A quantity of bytes read from values like `512`, `64KiB`, `10MB` or `1.5GiB`. Unit suffixes
+/// are case insensitive, the `i` makes the difference between IEC and SI units. Fractional
+/// quantities are rounded down to the byte.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ByteSize(u64);
+
+impl ByteSize {
+    pub fn new(bytes: u64) -> Self {
+        Self(bytes)
+    }
+
+    /// Number of bytes.
+    pub fn as_u64(&self) -> u64 {
+        self.0
+    }
+
+    /// Number of bytes, fails if it does not fit in a `usize` on this platform.
+    pub fn as_usize(&self) -> Result<usize, ConfigError> {
+        usize::try_from(self.0).map_err(|_| {
+            ConfigError::IncorrectValue(format!(
+                "byte size {} does not fit in this platform's memory size",
+                self.0
+            ))
+        })
+    }
+
+    /// Render the size using the largest unit of the given system, with at most two decimals. The
+    /// quantity is rounded down so it never exceeds the size, use
+    /// [to_exact](ByteSize::to_exact) for a text parsing back to the same size.
+    pub fn to_human(&self, system: ByteUnitSystem) -> String {
+        let (unit_size, unit) = self.unit(system, |unit_size| self.0 / unit_size >= system.base());
+
+        if self.0.is_multiple_of(unit_size) {
+            return format!("{}{unit}", self.0 / unit_size);
+        }
+
+        let hundredths = self.0 as u128 * 100 / unit_size as u128;
+        let quantity = format!("{}.{:02}", hundredths / 100, hundredths % 100);
+        let quantity = quantity.trim_end_matches('0').trim_end_matches('.');
+
+        format!("{quantity}{unit}")
+    }
+
+    /// Render the size using the largest unit of the given system dividing it evenly, so the text
+    /// parses back to the same size: `1536KiB` but `1365B`.
+    pub fn to_exact(&self, system: ByteUnitSystem) -> String {
+        let (unit_size, unit) = self.unit(system, |unit_size| {
+            self.0 != 0 && self.0.is_multiple_of(unit_size * system.base())
+        });
+
+        format!("{}{unit}", self.0 / unit_size)
+    }
+
+    /// Size and name of the largest unit of the given system to use, `larger` tells whether the
+    /// next unit can be used instead of the one of the given size.
+    fn unit(&self, system: ByteUnitSystem, larger: impl Fn(u64) -> bool) -> (u64, &'static str) {
+        let mut exponent = 0;
+        let mut unit_size: u64 = 1;
+
+        while exponent < 6 && larger(unit_size) {
+            unit_size *= system.base();
+            exponent += 1;
+        }
+
+        (unit_size, system.units()[exponent])
+    }
+
+    /// Parse a text like `512`, `64KiB`, `10MB` or `1.5GiB`.
+    pub fn parse(text: &str) -> Result<Self, ConfigError> {
+        let error = |reason: &str| {
+            ConfigError::IncorrectValue(format!("invalid byte size '{text}', {reason}"))
+        };
+        let trimmed = text.trim();
+
+        if trimmed.starts_with('-') {
+            return Err(error("byte sizes cannot be negative"));
+        }
+
+        let number_end = trimmed
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(trimmed.len());
+        let (number, suffix) = trimmed.split_at(number_end);
+        let multiplier = unit_multiplier(suffix.trim()).ok_or_else(|| {
+            error(&format!(
+                "unknown unit '{}' (accepted units are B, KB, MB, GB, TB, PB, EB and KiB, MiB, GiB, TiB, PiB, EiB)",
+                suffix.trim()
+            ))
+        })?;
+        let (integer, fraction) = number.split_once('.').unwrap_or((number, ""));
+
+        if integer.is_empty() && fraction.is_empty() {
+            return Err(error("expected a number like '512', '64KiB' or '1.5GiB'"));
+        }
+
+        if fraction.contains('.') || fraction.len() > 18 {
+            return Err(error("malformed number"));
+        }
+
+        let too_large = || error("the value is too large");
+        let integer: u128 = if integer.is_empty() {
+            0
+        } else {
+            integer.parse().map_err(|_| too_large())?
+        };
+        let fraction_bytes: u128 = if fraction.is_empty() {
+            0
+        } else {
+            let digits: u128 = fraction.parse().map_err(|_| error("malformed number"))?;
+
+            digits * multiplier as u128 / 10_u128.pow(fraction.len() as u32)
+        };
+        let bytes = integer
+            .checked_mul(multiplier as u128)
+            .and_then(|bytes| bytes.checked_add(fraction_bytes))
+            .and_then(|bytes| u64::try_from(bytes).ok())
+            .ok_or_else(too_large)?;
+
+        Ok(Self(bytes))
+    }
+}
+
+/// Number of bytes for the given unit suffix, an empty suffix means bytes.
+fn unit_multiplier(suffix: &str) -> Option<u64> {
+    if suffix.is_empty() {
+        return Some(1);
+    }
+
+    [ByteUnitSystem::Si, ByteUnitSystem::Iec]
+        .iter()
+        .find_map(|system| {
+            system
+                .units()
+                .iter()
+                .position(|unit| unit.eq_ignore_ascii_case(suffix))
+                .map(|exponent| system.base().pow(exponent as u32))
+        })
+}
+
+impl Display for ByteSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_human(ByteUnitSystem::Iec))
+    }
+}
+
+impl From<u64> for ByteSize {
+    fn from(value: u64) -> Self {
+        Self(value)
+    }
+}
+
+impl From<ByteSize> for FlatValue {
+    fn from(value: ByteSize) -> Self {
+        Self::Text(value.to_exact(ByteUnitSystem::Iec))
+    }
+}
+
+impl TryUnwrap<ByteSize> for FlatValue {
+    fn try_unwrap(&self) -> Result<ByteSize, ConfigError> {
+        match self {
+            Self::Integer(i) => u64::try_from(*i).map(ByteSize).map_err(|_| {
+                ConfigError::IncorrectValue(format!(
+                    "invalid byte size '{i}', byte sizes cannot be negative"
+                ))
+            }),
+            Self::Text(t) => ByteSize::parse(t),
+            _ => Err(ConfigError::TypeMismatch {
+                expected: "byte size".to_string(),
+                present: self.display(),
+            }),
+        }
+    }
+}
+
+impl FlatValue {
+    /// Convert a byte size value as a number of bytes.
+    pub fn try_unwrap_bytes(&self) -> Result<u64, ConfigError> {
+        TryUnwrap::<ByteSize>::try_unwrap(self).map(|size| size.as_u64())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> u64 {
+        ByteSize::parse(text).unwrap().as_u64()
+    }
+
+    #[test]
+    fn parse_units() {
+        assert_eq!(512, parse("512"));
+        assert_eq!(512, parse("512B"));
+        assert_eq!(65_536, parse("64KiB"));
+        assert_eq!(10_000_000, parse("10MB"));
+        assert_eq!(10_000_000, parse("10 mb"));
+        assert_eq!(1_610_612_736, parse("1.5GiB"));
+        assert_eq!(1_500, parse("1.5KB"));
+        assert_eq!(102, parse("0.1KiB"));
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(ByteSize::parse("-1KB").is_err());
+        assert!(ByteSize::parse("").is_err());
+        assert!(ByteSize::parse("KB").is_err());
+        assert!(ByteSize::parse("12 bananas").is_err());
+        assert!(ByteSize::parse("1.2.3MB").is_err());
+        assert!(ByteSize::parse("16EiB").is_err());
+        assert!(ByteSize::parse("99999999999999999999999").is_err());
+    }
+
+    #[test]
+    fn try_unwrap() {
+        let size: ByteSize = FlatValue::from("64KiB").try_unwrap().unwrap();
+        assert_eq!(ByteSize::new(65_536), size);
+
+        let size: ByteSize = FlatValue::Integer(4096).try_unwrap().unwrap();
+        assert_eq!(4096, size.as_usize().unwrap());
+
+        assert_eq!(1_000, FlatValue::from("1KB").try_unwrap_bytes().unwrap());
+
+        let result: Result<ByteSize, ConfigError> = FlatValue::Integer(-1).try_unwrap();
+        assert!(matches!(result, Err(ConfigError::IncorrectValue(_))));
+
+        let result: Result<ByteSize, ConfigError> = FlatValue::Boolean(true).try_unwrap();
+        assert!(matches!(result, Err(ConfigError::TypeMismatch { .. })));
+    }
+
+    #[test]
+    fn human() {
+        assert_eq!("512B", ByteSize::new(512).to_string());
+        assert_eq!("64KiB", ByteSize::new(65_536).to_string());
+        assert_eq!("1.5GiB", ByteSize::new(1_610_612_736).to_string());
+        assert_eq!("1.33KiB", ByteSize::new(1_365).to_string());
+        assert_eq!("15.99EiB", ByteSize::new(u64::MAX).to_string());
+        assert_eq!(
+            "10MB",
+            ByteSize::new(10_000_000).to_human(ByteUnitSystem::Si)
+        );
+        assert_eq!("999B", ByteSize::new(999).to_human(ByteUnitSystem::Si));
+    }
+
+    #[test]
+    fn exact() {
+        assert_eq!("0B", ByteSize::new(0).to_exact(ByteUnitSystem::Iec));
+        assert_eq!("1365B", ByteSize::new(1_365).to_exact(ByteUnitSystem::Iec));
+        assert_eq!(
+            "1536MiB",
+            ByteSize::new(1_610_612_736).to_exact(ByteUnitSystem::Iec)
+        );
+        assert_eq!(
+            "10MB",
+            ByteSize::new(10_000_000).to_exact(ByteUnitSystem::Si)
+        );
+        assert_eq!("8EiB", ByteSize::new(1 << 63).to_exact(ByteUnitSystem::Iec));
+    }
+
+    #[test]
+    fn round_trip() {
+        for bytes in [
+            0,
+            1,
+            999,
+            1_365,
+            65_536,
+            1_610_612_736,
+            10_000_000,
+            u64::MAX,
+        ] {
+            let size = ByteSize::new(bytes);
+            let value = FlatValue::from(size);
+
+            assert_eq!(size, value.try_unwrap().unwrap(), "{value:?}");
+            assert!(
+                ByteSize::parse(&size.to_string()).unwrap() <= size,
+                "{size}"
+            );
+        }
+    }
+}
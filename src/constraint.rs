@@ -0,0 +1,300 @@
+use std::{fmt::Debug, fmt::Display, sync::Arc};
+
+use crate::{pool::FlatPool, ConfigError, FlatValue};
+
+type Predicate = Arc<dyn Fn(&[Option<FlatValue>]) -> bool + Send + Sync>;
+
+#[derive(Clone)]
+enum Rule {
+    Requires,
+    ConflictsWith,
+    AtLeastOneOf,
+    ExactlyOneOf,
+    Custom {
+        description: String,
+        predicate: Predicate,
+    },
+}
+
+/// ## Constraints
+///
+/// Rules spanning several settings, checked once the individual values are valid. A setting is
+/// set when it is present with a value other than `false`, so a boolean flag turned off does not
+/// trigger its constraints. A violation produces a [ConfigError::Constraint] error naming all the
+/// settings of the constraint.
+///
+/// ```rust
+/// use flat_config::{Constraint, FlatValue, pool::SimpleFlatPool};
+///
+/// let mut pool = SimpleFlatPool::default();
+/// pool.add("tls.cert", "cert.pem".into())
+///     .add("min_connections", 10.into())
+///     .add("max_connections", 5.into());
+///
+/// assert!(Constraint::requires("tls.cert", &["tls.key"]).check(&pool).is_err());
+///
+/// let ordered = Constraint::custom(
+///     &["min_connections", "max_connections"],
+///     "min_connections must not exceed max_connections",
+///     |values| match values {
+///         [Some(FlatValue::Integer(min)), Some(FlatValue::Integer(max))] => min <= max,
+///         _ => true,
+///     },
+/// );
+/// assert!(ordered.check(&pool).is_err());
+/// ```
+#[derive(Clone)]
+pub struct Constraint {
+    keys: Vec<String>,
+    rule: Rule,
+}
+
+fn to_keys(keys: &[&str]) -> Vec<String> {
+    keys.iter().map(|key| key.to_string()).collect()
+}
+
+fn quoted(keys: &[String]) -> String {
+    let keys: Vec<String> = keys.iter().map(|key| format!("'{key}'")).collect();
+
+    keys.join(", ")
+}
+
+impl Constraint {
+    /// When `key` is set, all the `required` settings must be set.
+    pub fn requires(key: &str, required: &[&str]) -> Self {
+        let mut keys = vec![key.to_string()];
+        keys.extend(to_keys(required));
+
+        Self {
+            keys,
+            rule: Rule::Requires,
+        }
+    }
+
+    /// When `key` is set, none of the `conflicting` settings may be set.
+    pub fn conflicts_with(key: &str, conflicting: &[&str]) -> Self {
+        let mut keys = vec![key.to_string()];
+        keys.extend(to_keys(conflicting));
+
+        Self {
+            keys,
+            rule: Rule::ConflictsWith,
+        }
+    }
+
+    /// At least one of the settings must be set.
+    pub fn at_least_one_of(keys: &[&str]) -> Self {
+        Self {
+            keys: to_keys(keys),
+            rule: Rule::AtLeastOneOf,
+        }
+    }
+
+    /// Exactly one of the settings must be set.
+    pub fn exactly_one_of(keys: &[&str]) -> Self {
+        Self {
+            keys: to_keys(keys),
+            rule: Rule::ExactlyOneOf,
+        }
+    }
+
+    /// The predicate receives the values of the settings, in the given order, and tells whether
+    /// they are consistent. The description is the error message when they are not.
+    pub fn custom<F>(keys: &[&str], description: &str, predicate: F) -> Self
+    where
+        F: Fn(&[Option<FlatValue>]) -> bool + Send + Sync + 'static,
+    {
+        Self {
+            keys: to_keys(keys),
+            rule: Rule::Custom {
+                description: description.to_string(),
+                predicate: Arc::new(predicate),
+            },
+        }
+    }
+
+    /// Settings involved in the constraint.
+    pub fn keys(&self) -> &[String] {
+        &self.keys
+    }
+
+    /// Check the constraint against the given pool.
+    pub fn check(&self, pool: &(impl FlatPool + ?Sized)) -> Result<(), ConfigError> {
+        let is_set = |key: &String| {
+            pool.get_borrow(key)
+                .is_some_and(|value| value != &FlatValue::Boolean(false))
+        };
+        let set: Vec<&String> = self.keys.iter().filter(|key| is_set(key)).collect();
+        // The first key of `requires` and `conflicts_with` is the one triggering the rule.
+        let (first, others) = self.keys.split_at(1.min(self.keys.len()));
+        let triggered = first.iter().any(is_set);
+
+        let satisfied = match &self.rule {
+            Rule::Requires => !triggered || others.iter().all(is_set),
+            Rule::ConflictsWith => !triggered || !others.iter().any(is_set),
+            Rule::AtLeastOneOf => !set.is_empty(),
+            Rule::ExactlyOneOf => set.len() == 1,
+            Rule::Custom { predicate, .. } => {
+                let values: Vec<Option<FlatValue>> =
+                    self.keys.iter().map(|key| pool.get(key)).collect();
+
+                predicate(&values)
+            }
+        };
+
+        match satisfied {
+            true => Ok(()),
+            false => Err(ConfigError::Constraint {
+                keys: self.keys.clone(),
+                message: self.to_string(),
+            }),
+        }
+    }
+}
+
+impl Display for Constraint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (first, others) = self.keys.split_at(1.min(self.keys.len()));
+
+        match &self.rule {
+            Rule::Requires => write!(f, "{} requires {}", quoted(first), quoted(others)),
+            Rule::ConflictsWith => {
+                write!(f, "{} conflicts with {}", quoted(first), quoted(others))
+            }
+            Rule::AtLeastOneOf => write!(f, "at least one of {} must be set", quoted(&self.keys)),
+            Rule::ExactlyOneOf => write!(f, "exactly one of {} must be set", quoted(&self.keys)),
+            Rule::Custom { description, .. } => write!(f, "{description}"),
+        }
+    }
+}
+
+impl Debug for Constraint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Constraint")
+            .field("keys", &self.keys)
+            .field("rule", &self.to_string())
+            .finish()
+    }
+}
+
+impl PartialEq for Constraint {
+    fn eq(&self, other: &Self) -> bool {
+        let same_rule = match (&self.rule, &other.rule) {
+            (Rule::Custom { predicate: a, .. }, Rule::Custom { predicate: b, .. }) => {
+                Arc::ptr_eq(a, b)
+            }
+            (a, b) => std::mem::discriminant(a) == std::mem::discriminant(b),
+        };
+
+        same_rule && self.keys == other.keys && self.to_string() == other.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pool::SimpleFlatPool;
+
+    fn init_pool() -> SimpleFlatPool {
+        let mut pool = SimpleFlatPool::default();
+        pool.add("tls.cert", "cert.pem".into())
+            .add("tls.key", "key.pem".into())
+            .add("dry_run", true.into())
+            .add("verbose", false.into())
+            .add("min_connections", 2.into())
+            .add("max_connections", 10.into());
+
+        pool
+    }
+
+    fn keys_of(error: ConfigError) -> Vec<String> {
+        match error {
+            ConfigError::Constraint { keys, .. } => keys,
+            error => panic!("unexpected error {error:?}"),
+        }
+    }
+
+    #[test]
+    fn requires() {
+        let pool = init_pool();
+
+        assert!(Constraint::requires("tls.cert", &["tls.key"])
+            .check(&pool)
+            .is_ok());
+        assert!(Constraint::requires("unset", &["missing"])
+            .check(&pool)
+            .is_ok());
+        assert!(Constraint::requires("verbose", &["log.file"])
+            .check(&pool)
+            .is_ok());
+
+        let error = Constraint::requires("tls.cert", &["tls.key", "tls.ca"])
+            .check(&pool)
+            .unwrap_err();
+        assert_eq!(
+            "CONFIGURATION ERROR: Settings 'tls.cert', 'tls.key', 'tls.ca': 'tls.cert' requires 'tls.key', 'tls.ca'.",
+            error.to_string()
+        );
+        assert_eq!(vec!["tls.cert", "tls.key", "tls.ca"], keys_of(error));
+    }
+
+    #[test]
+    fn conflicts_with() {
+        let pool = init_pool();
+
+        assert!(Constraint::conflicts_with("dry_run", &["verbose"])
+            .check(&pool)
+            .is_ok());
+
+        let error = Constraint::conflicts_with("dry_run", &["tls.cert"])
+            .check(&pool)
+            .unwrap_err();
+        assert_eq!(vec!["dry_run", "tls.cert"], keys_of(error));
+    }
+
+    #[test]
+    fn one_of() {
+        let pool = init_pool();
+
+        assert!(Constraint::at_least_one_of(&["tls.cert", "plain"])
+            .check(&pool)
+            .is_ok());
+        assert!(Constraint::at_least_one_of(&["verbose", "plain"])
+            .check(&pool)
+            .is_err());
+        assert!(Constraint::exactly_one_of(&["tls.cert", "plain"])
+            .check(&pool)
+            .is_ok());
+
+        let error = Constraint::exactly_one_of(&["tls.cert", "tls.key"])
+            .check(&pool)
+            .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("exactly one of 'tls.cert', 'tls.key' must be set"));
+    }
+
+    #[test]
+    fn custom() {
+        let ordered = |values: &[Option<FlatValue>]| match values {
+            [Some(FlatValue::Integer(min)), Some(FlatValue::Integer(max))] => min <= max,
+            _ => true,
+        };
+        let constraint = Constraint::custom(
+            &["min_connections", "max_connections"],
+            "min_connections must not exceed max_connections",
+            ordered,
+        );
+        let mut pool = init_pool();
+
+        assert!(constraint.check(&pool).is_ok());
+
+        pool.add("min_connections", 20.into());
+        let error = constraint.check(&pool).unwrap_err();
+        assert!(matches!(
+            error,
+            ConfigError::Constraint { ref message, .. } if message == "min_connections must not exceed max_connections"
+        ));
+        assert_eq!(constraint, constraint.clone());
+    }
+}
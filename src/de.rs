@@ -0,0 +1,515 @@
+use std::{collections::BTreeSet, fmt::Display};
+
+use serde::de::{
+    self,
+    value::{MapDeserializer, SeqDeserializer},
+    DeserializeOwned, IntoDeserializer, Visitor,
+};
+
+use crate::{pool::FlatPool, ConfigBuilder, ConfigError, FlatValue};
+
+impl de::Error for ConfigError {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self::IncorrectValue(msg.to_string())
+    }
+
+    fn invalid_type(unexp: de::Unexpected, exp: &dyn de::Expected) -> Self {
+        Self::TypeMismatch {
+            expected: exp.to_string(),
+            present: unexp.to_string(),
+        }
+    }
+
+    // The path of the field is not known here, it is completed by the structure deserializer
+    // using an empty field list as a marker.
+    fn missing_field(field: &'static str) -> Self {
+        Self::Missing {
+            field_name: field.to_string(),
+            fields: String::new(),
+        }
+    }
+}
+
+/// ## Pool deserializer
+///
+/// Serde deserializer reading data structures from a [FlatPool]. Dotted keys are mapped onto
+/// nested structures (`database.port` is the field `port` of the structure in the field
+/// `database`), options are `None` when the key is not present and sequences are read either
+/// from indexed keys (`servers.0`, `servers.1`…) or from a comma separated text.
+///
+/// ```rust
+/// use flat_config::{from_pool, pool::SimpleFlatPool};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Database {
+///     host: String,
+///     port: u16,
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct AppConfig {
+///     database: Database,
+///     dry_run: Option<bool>,
+/// }
+///
+/// let mut pool = SimpleFlatPool::default();
+/// pool.add("database.host", "localhost".into())
+///     .add("database.port", 5432.into());
+/// let config: AppConfig = from_pool(&pool).unwrap();
+///
+/// assert_eq!(5432, config.database.port);
+/// assert_eq!(None, config.dry_run);
+/// ```
+#[derive(Debug)]
+pub struct PoolDeserializer<'a, P: FlatPool + ?Sized> {
+    pool: &'a P,
+    path: String,
+}
+
+impl<'a, P: FlatPool + ?Sized> PoolDeserializer<'a, P> {
+    pub fn new(pool: &'a P) -> Self {
+        Self {
+            pool,
+            path: String::new(),
+        }
+    }
+
+    fn child(&self, segment: &str) -> Self {
+        let path = if self.path.is_empty() {
+            segment.to_string()
+        } else {
+            format!("{}.{segment}", self.path)
+        };
+
+        Self {
+            pool: self.pool,
+            path,
+        }
+    }
+
+    fn value(&self) -> Option<FlatValue> {
+        if self.path.is_empty() {
+            None
+        } else {
+            self.pool.get(&self.path)
+        }
+    }
+
+    /// Distinct key segments directly under this path.
+    fn children(&self) -> BTreeSet<String> {
+        let prefix = if self.path.is_empty() {
+            String::new()
+        } else {
+            format!("{}.", self.path)
+        };
+
+        self.pool
+            .keys()
+            .iter()
+            .filter_map(|key| key.strip_prefix(&prefix))
+            .filter_map(|rest| rest.split('.').next())
+            .map(|segment| segment.to_string())
+            .collect()
+    }
+
+    fn exists(&self) -> bool {
+        self.path.is_empty() || self.value().is_some() || !self.children().is_empty()
+    }
+
+    fn missing(&self) -> ConfigError {
+        ConfigError::Missing {
+            field_name: self.path.clone(),
+            fields: format!("{:?}", self.pool),
+        }
+    }
+
+    fn visit_map<'de, V: Visitor<'de>>(
+        self,
+        segments: Vec<String>,
+        visitor: V,
+    ) -> Result<V::Value, ConfigError> {
+        let entries = segments.into_iter().map(|segment| {
+            let child = self.child(&segment);
+
+            (segment, child)
+        });
+        let mut map = MapDeserializer::new(entries);
+        let result = visitor
+            .visit_map(&mut map)
+            .and_then(|value| map.end().map(|_| value));
+
+        result.map_err(|error| match error {
+            ConfigError::Missing { field_name, fields } if fields.is_empty() => {
+                self.child(&field_name).missing()
+            }
+            error => error,
+        })
+    }
+}
+
+impl<'de, P: FlatPool + ?Sized> IntoDeserializer<'de, ConfigError> for PoolDeserializer<'_, P> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+impl<'de, P: FlatPool + ?Sized> de::Deserializer<'de> for PoolDeserializer<'_, P> {
+    type Error = ConfigError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        if let Some(value) = self.value() {
+            return ValueDeserializer::new(value, &self.path).deserialize_any(visitor);
+        }
+
+        if self.exists() {
+            return self.deserialize_map(visitor);
+        }
+
+        Err(self.missing())
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        if self.exists() {
+            visitor.visit_some(self)
+        } else {
+            visitor.visit_none()
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        let segments = fields
+            .iter()
+            .filter(|field| self.child(field).exists())
+            .map(|field| field.to_string())
+            .collect();
+
+        self.visit_map(segments, visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let segments = self.children().into_iter().collect();
+
+        self.visit_map(segments, visitor)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let mut indices: Vec<usize> = self
+            .children()
+            .iter()
+            .filter_map(|segment| segment.parse().ok())
+            .collect();
+
+        if !indices.is_empty() {
+            indices.sort_unstable();
+            let elements = indices
+                .into_iter()
+                .map(|index| self.child(&index.to_string()));
+            let mut seq = SeqDeserializer::new(elements);
+            let value = visitor.visit_seq(&mut seq)?;
+            seq.end()?;
+
+            return Ok(value);
+        }
+
+        let value = self.value().ok_or_else(|| self.missing())?;
+        let elements: Vec<ValueDeserializer> = match &value {
+            FlatValue::Text(t) if t.trim().is_empty() => Vec::new(),
+            FlatValue::Text(t) => t
+                .split(',')
+                .enumerate()
+                .map(|(index, item)| {
+                    ValueDeserializer::new(
+                        FlatValue::Text(item.trim().to_string()),
+                        &format!("{}.{index}", self.path),
+                    )
+                })
+                .collect(),
+            _ => vec![ValueDeserializer::new(value.clone(), &self.path)],
+        };
+        let mut seq = SeqDeserializer::new(elements.into_iter());
+        let value = visitor.visit_seq(&mut seq)?;
+        seq.end()?;
+
+        Ok(value)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        match self.value() {
+            Some(value) => {
+                ValueDeserializer::new(value, &self.path).deserialize_enum(name, variants, visitor)
+            }
+            None => Err(self.missing()),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct tuple tuple_struct identifier ignored_any
+    }
+}
+
+/// Deserializer of a single flat value, errors are attached to the setting's key.
+struct ValueDeserializer {
+    value: FlatValue,
+    key: String,
+}
+
+impl ValueDeserializer {
+    fn new(value: FlatValue, key: &str) -> Self {
+        Self {
+            value,
+            key: key.to_string(),
+        }
+    }
+}
+
+impl<'de> IntoDeserializer<'de, ConfigError> for ValueDeserializer {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+impl<'de> de::Deserializer<'de> for ValueDeserializer {
+    type Error = ConfigError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let result: Result<V::Value, ConfigError> = match self.value {
+            FlatValue::Integer(i) => visitor.visit_i64(i as i64),
+            FlatValue::Text(t) => visitor.visit_string(t),
+            FlatValue::Boolean(b) => visitor.visit_bool(b),
+        };
+
+        result.map_err(|error| error.with_key(&self.key))
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        let result: Result<V::Value, ConfigError> = match self.value {
+            FlatValue::Text(t) => {
+                visitor.visit_enum(IntoDeserializer::<ConfigError>::into_deserializer(t))
+            }
+            value => Err(ConfigError::TypeMismatch {
+                expected: "text".to_string(),
+                present: value.display(),
+            }),
+        };
+
+        result.map_err(|error| error.with_key(&self.key))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+/// Deserialize a data structure from the given pool.
+pub fn from_pool<T: DeserializeOwned, P: FlatPool + ?Sized>(pool: &P) -> Result<T, ConfigError> {
+    T::deserialize(PoolDeserializer::new(pool))
+}
+
+/// ## Serde configuration builder
+///
+/// Configuration builder for any configuration structure implementing serde's `Deserialize`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SerdeConfigBuilder;
+
+impl<T: DeserializeOwned> ConfigBuilder<T> for SerdeConfigBuilder {
+    fn build(&self, config_pool: &impl FlatPool) -> Result<T, ConfigError> {
+        from_pool(config_pool)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::pool::{LayeredFlatPool, SimpleFlatPool};
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(rename_all = "lowercase")]
+    enum LogFormat {
+        Json,
+        Text,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Database {
+        host: String,
+        port: u16,
+        #[serde(default)]
+        pool_size: usize,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct AppConfig {
+        app_name: String,
+        database: Database,
+        replica: Option<Database>,
+        dry_run: Option<bool>,
+        log_format: LogFormat,
+        #[serde(default)]
+        tags: Vec<String>,
+    }
+
+    fn init_pool() -> SimpleFlatPool {
+        let mut pool = SimpleFlatPool::default();
+        pool.add("app_name", "whatever".into())
+            .add("database.host", "localhost".into())
+            .add("database.port", 5432.into())
+            .add("log_format", "json".into());
+
+        pool
+    }
+
+    #[test]
+    fn nested_structures() {
+        let config: AppConfig = from_pool(&init_pool()).unwrap();
+
+        assert_eq!(
+            AppConfig {
+                app_name: "whatever".to_string(),
+                database: Database {
+                    host: "localhost".to_string(),
+                    port: 5432,
+                    pool_size: 0,
+                },
+                replica: None,
+                dry_run: None,
+                log_format: LogFormat::Json,
+                tags: Vec::new(),
+            },
+            config
+        );
+    }
+
+    #[test]
+    fn options() {
+        let mut pool = init_pool();
+        pool.add("dry_run", true.into())
+            .add("replica.host", "replica".into())
+            .add("replica.port", 5433.into());
+        let config: AppConfig = from_pool(&pool).unwrap();
+
+        assert_eq!(Some(true), config.dry_run);
+        assert_eq!(5433, config.replica.unwrap().port);
+    }
+
+    #[test]
+    fn sequences() {
+        let mut pool = init_pool();
+        pool.add("tags", "a, b,c".into());
+        let config: AppConfig = from_pool(&pool).unwrap();
+        assert_eq!(vec!["a", "b", "c"], config.tags);
+
+        let mut pool = init_pool();
+        pool.add("tags.0", "first".into())
+            .add("tags.2", "third".into())
+            .add("tags.1", "second".into());
+        let config: AppConfig = from_pool(&pool).unwrap();
+        assert_eq!(vec!["first", "second", "third"], config.tags);
+    }
+
+    #[test]
+    fn maps() {
+        let mut pool = SimpleFlatPool::default();
+        pool.add("a", 1.into()).add("b", 2.into());
+        let map: HashMap<String, isize> = from_pool(&pool).unwrap();
+
+        assert_eq!(Some(&2), map.get("b"));
+        assert_eq!(2, map.len());
+    }
+
+    #[test]
+    fn layered() {
+        let mut file_pool = init_pool();
+        file_pool.add("database.port", 1.into());
+        let mut parameter_pool = SimpleFlatPool::default();
+        parameter_pool.add("database.port", 6432.into());
+        let pool = LayeredFlatPool::new(vec![Box::new(file_pool), Box::new(parameter_pool)]);
+        let config: AppConfig = SerdeConfigBuilder.build(&pool).unwrap();
+
+        assert_eq!(6432, config.database.port);
+    }
+
+    #[test]
+    fn missing_path() {
+        let mut pool = init_pool();
+        pool.add("replica.host", "replica".into());
+        let error = from_pool::<AppConfig, _>(&pool).unwrap_err();
+
+        assert!(
+            matches!(error, ConfigError::Missing { ref field_name, .. } if field_name == "replica.port"),
+            "{error:?}"
+        );
+    }
+
+    #[test]
+    fn type_mismatch_path() {
+        let mut pool = init_pool();
+        pool.add("database.port", "five".into());
+        let error = from_pool::<AppConfig, _>(&pool).unwrap_err();
+        assert!(
+            matches!(error, ConfigError::Setting { ref key, .. } if key == "database.port"),
+            "{error:?}"
+        );
+
+        let mut pool = init_pool();
+        pool.add("database.port", 70_000.into());
+        let error = from_pool::<AppConfig, _>(&pool).unwrap_err();
+        assert!(
+            matches!(error, ConfigError::Setting { ref key, .. } if key == "database.port"),
+            "{error:?}"
+        );
+
+        let mut pool = init_pool();
+        pool.add("log_format", "xml".into());
+        let error = from_pool::<AppConfig, _>(&pool).unwrap_err();
+        assert!(
+            matches!(error, ConfigError::Setting { ref key, .. } if key == "log_format"),
+            "{error:?}"
+        );
+    }
+}
@@ -0,0 +1,228 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+};
+
+use crate::{pool::FlatPool, FlatValue, REDACTED};
+
+/// ## Setting change
+///
+/// How a setting differs between two pools.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    Added { new: FlatValue },
+    Removed { old: FlatValue },
+    Changed { old: FlatValue, new: FlatValue },
+}
+
+/// ## Pool diff
+///
+/// Settings added, removed or changed between two pools. Values of settings that are secret in
+/// either pool are replaced by [REDACTED], a secret setting whose value changed is still reported.
+/// The `Display` implementation renders one line per setting:
+///
+/// ```rust
+/// use flat_config::{PoolDiff, pool::SimpleFlatPool};
+///
+/// let mut old = SimpleFlatPool::default();
+/// old.add("port", 80.into()).add("debug", true.into());
+/// let mut new = SimpleFlatPool::default();
+/// new.add("port", 8080.into()).add("name", "web".into());
+/// let diff = PoolDiff::new(&old, &new);
+///
+/// assert_eq!("- debug = true\n+ name = \"web\"\n~ port = 80 → 8080\n", diff.to_string());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PoolDiff {
+    changes: BTreeMap<String, Change>,
+    secrets: BTreeSet<String>,
+}
+
+impl PoolDiff {
+    /// Compare the settings of the `old` pool with the settings of the `new` one.
+    pub fn new(old: &(impl FlatPool + ?Sized), new: &(impl FlatPool + ?Sized)) -> Self {
+        let mut diff = Self::default();
+        let keys: BTreeSet<String> = old.keys().into_iter().chain(new.keys()).collect();
+
+        for key in keys {
+            let secret = old.is_secret(&key) || new.is_secret(&key);
+            let redact = |value: &FlatValue| match secret {
+                true => FlatValue::from(REDACTED),
+                false => value.clone(),
+            };
+            let change = match (old.get_borrow(&key), new.get_borrow(&key)) {
+                (Some(old), Some(new)) if old == new => continue,
+                (Some(old), Some(new)) => Change::Changed {
+                    old: redact(old),
+                    new: redact(new),
+                },
+                (Some(old), None) => Change::Removed { old: redact(old) },
+                (None, Some(new)) => Change::Added { new: redact(new) },
+                (None, None) => continue,
+            };
+
+            if secret {
+                diff.secrets.insert(key.clone());
+            }
+            diff.changes.insert(key, change);
+        }
+
+        diff
+    }
+
+    /// True when both pools hold the same settings.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Number of settings that differ.
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    /// Change of the given setting, if any.
+    pub fn get(&self, key: &str) -> Option<&Change> {
+        self.changes.get(key)
+    }
+
+    /// True when the values of the given setting are redacted.
+    pub fn is_secret(&self, key: &str) -> bool {
+        self.secrets.contains(key)
+    }
+
+    /// Keys of the settings that differ, sorted.
+    pub fn keys(&self) -> BTreeSet<String> {
+        self.changes.keys().cloned().collect()
+    }
+
+    /// Settings that differ with their change, sorted by key.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Change)> {
+        self.changes
+            .iter()
+            .map(|(key, change)| (key.as_str(), change))
+    }
+
+    /// Settings only present in the new pool.
+    pub fn added(&self) -> impl Iterator<Item = (&str, &FlatValue)> {
+        self.iter().filter_map(|(key, change)| match change {
+            Change::Added { new } => Some((key, new)),
+            _ => None,
+        })
+    }
+
+    /// Settings only present in the old pool.
+    pub fn removed(&self) -> impl Iterator<Item = (&str, &FlatValue)> {
+        self.iter().filter_map(|(key, change)| match change {
+            Change::Removed { old } => Some((key, old)),
+            _ => None,
+        })
+    }
+
+    /// Settings present in both pools with different values, as `(key, old, new)`.
+    pub fn changed(&self) -> impl Iterator<Item = (&str, &FlatValue, &FlatValue)> {
+        self.iter().filter_map(|(key, change)| match change {
+            Change::Changed { old, new } => Some((key, old, new)),
+            _ => None,
+        })
+    }
+
+    fn render(&self, key: &str, value: &FlatValue) -> String {
+        if self.is_secret(key) {
+            return REDACTED.to_string();
+        }
+
+        value.literal()
+    }
+}
+
+impl Display for PoolDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (key, change) in self.iter() {
+            match change {
+                Change::Added { new } => writeln!(f, "+ {key} = {}", self.render(key, new))?,
+                Change::Removed { old } => writeln!(f, "- {key} = {}", self.render(key, old))?,
+                Change::Changed { old, new } => writeln!(
+                    f,
+                    "~ {key} = {} → {}",
+                    self.render(key, old),
+                    self.render(key, new)
+                )?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pool::SimpleFlatPool;
+
+    fn init_pools() -> (SimpleFlatPool, SimpleFlatPool) {
+        let mut old = SimpleFlatPool::default();
+        old.add("port", 80.into())
+            .add("name", "web".into())
+            .add("debug", true.into())
+            .add_secret("password", "hunter2".into());
+        let mut new = SimpleFlatPool::default();
+        new.add("port", 8080.into())
+            .add("name", "web".into())
+            .add("log", "info".into())
+            .add("password", "hunter3".into());
+
+        (old, new)
+    }
+
+    #[test]
+    fn diff() {
+        let (old, new) = init_pools();
+        let diff = PoolDiff::new(&old, &new);
+
+        assert_eq!(4, diff.len());
+        assert_eq!(None, diff.get("name"));
+        assert_eq!(
+            vec![("log", &FlatValue::from("info"))],
+            diff.added().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![("debug", &FlatValue::Boolean(true))],
+            diff.removed().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            Some(&Change::Changed {
+                old: FlatValue::Integer(80),
+                new: FlatValue::Integer(8080)
+            }),
+            diff.get("port")
+        );
+        assert_eq!(2, diff.changed().count());
+        assert!(PoolDiff::new(&old, &old).is_empty());
+    }
+
+    #[test]
+    fn secrets() {
+        let (old, new) = init_pools();
+        let diff = PoolDiff::new(&old, &new);
+
+        assert!(diff.is_secret("password"));
+        assert_eq!(
+            Some(&Change::Changed {
+                old: FlatValue::from(REDACTED),
+                new: FlatValue::from(REDACTED)
+            }),
+            diff.get("password")
+        );
+        assert!(!format!("{diff:?}").contains("hunter"));
+    }
+
+    #[test]
+    fn render() {
+        let (old, new) = init_pools();
+
+        assert_eq!(
+            "- debug = true\n+ log = \"info\"\n~ password = *** → ***\n~ port = 80 → 8080\n",
+            PoolDiff::new(&old, &new).to_string()
+        );
+    }
+}
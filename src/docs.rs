@@ -0,0 +1,627 @@
+use std::{collections::BTreeMap, fmt::Write};
+
+use crate::{
+    cli_flag_name, env_var_name,
+    export::{quote, scalar, toml_key},
+    nested, FlatValue, Schema, SettingSchema, SettingType, Validator,
+};
+
+/// ## Setting names
+///
+/// Names a setting is known by outside of the configuration files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SettingNames {
+    pub key: String,
+    pub env_var: String,
+    pub cli_flag: String,
+}
+
+/// ## Schema documentation
+///
+/// Reference documentation generated from a [Schema]: a Markdown table, a commented sample TOML
+/// file, the environment variables and command line flags of each setting and a JSON Schema
+/// editors can use to validate and complete configuration files. Generating it from
+/// a test or a build script keeps the documentation in line with the code reading the settings.
+///
+/// ```rust
+/// use flat_config::{Schema, SchemaDocs, SettingSchema, SettingType};
+///
+/// let mut schema = Schema::default();
+/// schema.add(
+///     SettingSchema::new("server.port", SettingType::Integer)
+///         .description("Port the server listens on.")
+///         .default_value(8080.into()),
+/// );
+/// let docs = SchemaDocs::new(&schema).env_prefix("APP_");
+///
+/// assert_eq!("APP_SERVER__PORT", docs.names()[0].env_var);
+/// assert!(docs.sample_toml().contains("[server]\n# Port the server listens on.\n"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct SchemaDocs<'a> {
+    schema: &'a Schema,
+    env_prefix: String,
+}
+
+impl<'a> SchemaDocs<'a> {
+    pub fn new(schema: &'a Schema) -> Self {
+        Self {
+            schema,
+            env_prefix: String::new(),
+        }
+    }
+
+    /// Prefix of the environment variable names, like `APP_`.
+    pub fn env_prefix(mut self, prefix: &str) -> Self {
+        self.env_prefix = prefix.to_string();
+
+        self
+    }
+
+    /// Environment variable and command line flag of each setting, in the schema order.
+    pub fn names(&self) -> Vec<SettingNames> {
+        self.schema
+            .settings()
+            .iter()
+            .map(|setting| SettingNames {
+                key: setting.key.clone(),
+                env_var: env_var_name(&self.env_prefix, &setting.key),
+                cli_flag: cli_flag_name(&setting.key),
+            })
+            .collect()
+    }
+
+    /// Markdown table describing every setting.
+    pub fn markdown(&self) -> String {
+        let mut output = String::from(
+            "| Setting | Type | Default | Required | Environment | Flag | Description |\n\
+             |---|---|---|---|---|---|---|\n",
+        );
+
+        for (setting, names) in self.schema.settings().iter().zip(self.names()) {
+            let default = setting
+                .default
+                .as_ref()
+                .map(|value| format!("`{}`", scalar(value)))
+                .unwrap_or_default();
+            let required = match setting.required {
+                true => "yes",
+                false => "no",
+            };
+            let mut description: Vec<String> = setting.description.iter().cloned().collect();
+            description.extend(constraints(setting).iter().map(|c| sentence(c)));
+
+            if !setting.validators.is_empty() {
+                let rules: Vec<String> = setting
+                    .validators
+                    .iter()
+                    .map(|validator| format!("`{validator}`"))
+                    .collect();
+                description.push(format!("Rules: {}.", rules.join(", ")));
+            }
+
+            if !setting.aliases.is_empty() {
+                let aliases: Vec<String> = setting
+                    .aliases
+                    .iter()
+                    .map(|alias| format!("`{alias}`"))
+                    .collect();
+                description.push(format!("Deprecated aliases: {}.", aliases.join(", ")));
+            }
+            if let Some(message) = &setting.deprecated {
+                description.push(format!("**Deprecated**: {message}"));
+            }
+
+            let _ = writeln!(
+                output,
+                "| `{}` | {} | {default} | {required} | `{}` | `{}` | {} |",
+                setting.key,
+                setting.setting_type,
+                names.env_var,
+                names.cli_flag,
+                description.join(" ").replace('|', "\\|").replace('\n', " ")
+            );
+        }
+
+        if !self.schema.constraints().is_empty() {
+            output.push_str("\nConstraints:\n\n");
+
+            for constraint in self.schema.constraints() {
+                let _ = writeln!(output, "- {constraint}");
+            }
+        }
+
+        output
+    }
+
+    /// Sample TOML file with every setting, commented with its documentation. Settings without a
+    /// default value are commented out. A setting nested under another one, like `tls.cert` under
+    /// `tls`, is written in the root table under its quoted key.
+    pub fn sample_toml(&self) -> String {
+        let keys = self
+            .schema
+            .settings()
+            .iter()
+            .map(|setting| (&setting.key, ()));
+        let (_, conflicts) = nested::Table::nest(keys);
+        let mut sections: BTreeMap<&str, Vec<(&str, &SettingSchema)>> = BTreeMap::new();
+
+        for setting in self.schema.settings() {
+            let (section, name) = if conflicts.iter().any(|c| c.child == setting.key) {
+                ("", setting.key.as_str())
+            } else {
+                setting.key.rsplit_once('.').unwrap_or(("", &setting.key))
+            };
+            sections.entry(section).or_default().push((name, setting));
+        }
+
+        let mut blocks: Vec<String> = Vec::new();
+
+        // The root section comes first as it has no header.
+        for (section, settings) in sections {
+            let mut block = String::new();
+
+            if !section.is_empty() {
+                let header: Vec<String> = section.split('.').map(toml_key).collect();
+                let _ = writeln!(block, "[{}]", header.join("."));
+            }
+
+            let entries: Vec<String> = settings
+                .into_iter()
+                .map(|(name, setting)| self.toml_entry(name, setting))
+                .collect();
+            block.push_str(&entries.join("\n"));
+            blocks.push(block);
+        }
+
+        blocks.join("\n")
+    }
+
+    fn toml_entry(&self, name: &str, setting: &SettingSchema) -> String {
+        let mut entry = String::new();
+
+        if let Some(description) = &setting.description {
+            for line in description.lines() {
+                let _ = writeln!(entry, "# {line}");
+            }
+        }
+
+        let mut details = vec![setting.setting_type.to_string()];
+
+        if setting.required {
+            details.push("required".to_string());
+        }
+        details.extend(constraints(setting));
+        let _ = writeln!(entry, "# Type: {}", details.join(", "));
+
+        if !setting.validators.is_empty() {
+            let rules: Vec<String> = setting.validators.iter().map(|v| v.to_string()).collect();
+            let _ = writeln!(entry, "# Rules: {}", rules.join(", "));
+        }
+        let _ = writeln!(
+            entry,
+            "# Environment: {}, flag: {}",
+            env_var_name(&self.env_prefix, &setting.key),
+            cli_flag_name(&setting.key)
+        );
+
+        if let Some(message) = &setting.deprecated {
+            let _ = writeln!(entry, "# Deprecated: {message}");
+        }
+
+        match &setting.default {
+            Some(value) => {
+                let _ = writeln!(entry, "{} = {}", toml_key(name), scalar(value));
+            }
+            None => {
+                let _ = writeln!(entry, "# {} =", toml_key(name));
+            }
+        }
+
+        entry
+    }
+
+    /// JSON Schema (draft 2020-12) of the configuration files, dotted keys are nested objects.
+    pub fn json_schema(&self) -> String {
+        let settings = self.schema.settings().iter();
+        let (root, _) = ObjectSchema::nest(settings.map(|setting| (&setting.key, setting)));
+
+        let mut entries = vec![(
+            "$schema".to_string(),
+            Json::from("https://json-schema.org/draft/2020-12/schema"),
+        )];
+        entries.extend(object_entries(&root));
+
+        let mut output = String::new();
+        Json::Object(entries).render(0, &mut output);
+        output.push('\n');
+
+        output
+    }
+}
+
+/// JSON document.
+enum Json {
+    Value(FlatValue),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl From<&str> for Json {
+    fn from(value: &str) -> Self {
+        Self::Value(FlatValue::from(value))
+    }
+}
+
+impl Json {
+    fn render(&self, depth: usize, output: &mut String) {
+        let indent = "  ".repeat(depth + 1);
+
+        match self {
+            Self::Value(value) => output.push_str(&scalar(value)),
+            Self::Array(elements) => {
+                let elements: Vec<String> = elements
+                    .iter()
+                    .map(|element| {
+                        let mut rendered = String::new();
+                        element.render(depth + 1, &mut rendered);
+
+                        rendered
+                    })
+                    .collect();
+                let _ = write!(output, "[{}]", elements.join(", "));
+            }
+            Self::Object(entries) => {
+                output.push_str("{\n");
+
+                for (index, (key, value)) in entries.iter().enumerate() {
+                    if index > 0 {
+                        output.push_str(",\n");
+                    }
+                    let _ = write!(output, "{indent}{}: ", quote(key));
+                    value.render(depth + 1, output);
+                }
+                let _ = write!(output, "\n{}}}", "  ".repeat(depth));
+            }
+        }
+    }
+}
+
+/// Settings nested according to their dotted keys.
+type ObjectSchema<'a> = nested::Table<&'a SettingSchema>;
+type PropertySchema<'a> = nested::Node<&'a SettingSchema>;
+
+fn object_entries(object: &ObjectSchema) -> Vec<(String, Json)> {
+    let properties = object
+        .0
+        .iter()
+        .map(|(name, property)| (name.clone(), property_json(property)))
+        .collect();
+    let required: Vec<Json> = object
+        .0
+        .iter()
+        .filter(|(_, property)| is_required(property))
+        .map(|(name, _)| Json::from(name.as_str()))
+        .collect();
+    let mut entries = vec![
+        ("type".to_string(), Json::from("object")),
+        ("properties".to_string(), Json::Object(properties)),
+    ];
+
+    if !required.is_empty() {
+        entries.push(("required".to_string(), Json::Array(required)));
+    }
+
+    entries
+}
+
+/// Required settings without default value must be written in the file, so must the objects
+/// holding them.
+fn is_required(property: &PropertySchema) -> bool {
+    match property {
+        PropertySchema::Value(setting) => setting.required && setting.default.is_none(),
+        PropertySchema::Table(object) => object.0.values().any(is_required),
+    }
+}
+
+fn property_json(property: &PropertySchema) -> Json {
+    let setting = match property {
+        PropertySchema::Value(setting) => setting,
+        PropertySchema::Table(object) => return Json::Object(object_entries(object)),
+    };
+    let json_type = match setting.setting_type {
+        SettingType::Integer => "integer",
+        SettingType::Text => "string",
+        SettingType::Boolean => "boolean",
+    };
+    let mut entries = vec![("type".to_string(), Json::from(json_type))];
+
+    if let Some(description) = &setting.description {
+        entries.push(("description".to_string(), Json::from(description.as_str())));
+    }
+    if let Some(default) = &setting.default {
+        entries.push(("default".to_string(), Json::Value(default.clone())));
+    }
+    if let Some(min) = setting.min {
+        entries.push(("minimum".to_string(), Json::Value(FlatValue::Integer(min))));
+    }
+    if let Some(max) = setting.max {
+        entries.push(("maximum".to_string(), Json::Value(FlatValue::Integer(max))));
+    }
+    if !setting.allowed.is_empty() {
+        let allowed = setting.allowed.iter().cloned().map(Json::Value).collect();
+        entries.push(("enum".to_string(), Json::Array(allowed)));
+    }
+    for validator in &setting.validators {
+        json_rule(validator, &mut entries);
+    }
+    if setting.deprecated.is_some() {
+        entries.push((
+            "deprecated".to_string(),
+            Json::Value(FlatValue::Boolean(true)),
+        ));
+    }
+
+    Json::Object(entries)
+}
+
+/// Add the JSON Schema keywords expressing the given rule, unless already present. Rules JSON
+/// Schema cannot express are left to the validation of the loaded settings.
+fn json_rule(validator: &Validator, entries: &mut Vec<(String, Json)>) {
+    let mut keyword = |name: &str, value: Json| {
+        if !entries.iter().any(|(key, _)| key == name) {
+            entries.push((name.to_string(), value));
+        }
+    };
+    let integer =
+        |i: usize| Json::Value(FlatValue::Integer(isize::try_from(i).unwrap_or(isize::MAX)));
+
+    match validator {
+        Validator::Range { min, max } => {
+            if let Some(min) = min {
+                keyword("minimum", Json::Value(FlatValue::Integer(*min)));
+            }
+            if let Some(max) = max {
+                keyword("maximum", Json::Value(FlatValue::Integer(*max)));
+            }
+        }
+        Validator::Length { min, max } => {
+            if let Some(min) = min {
+                keyword("minLength", integer(*min));
+            }
+            if let Some(max) = max {
+                keyword("maxLength", integer(*max));
+            }
+        }
+        #[cfg(feature = "regex")]
+        Validator::Matches(regex) => keyword("pattern", Json::from(regex.as_str())),
+        Validator::NotEmpty => keyword("minLength", integer(1)),
+        Validator::OneOf(values) => keyword(
+            "enum",
+            Json::Array(values.iter().cloned().map(Json::Value).collect()),
+        ),
+        Validator::PathExists | Validator::UrlScheme(_) => {}
+    }
+}
+
+/// Constraint as a sentence of the Markdown description.
+fn sentence(constraint: &str) -> String {
+    let mut chars = constraint.chars();
+
+    match chars.next() {
+        Some(first) => format!("{}{}.", first.to_uppercase(), chars.as_str()),
+        None => String::new(),
+    }
+}
+
+/// Human readable constraints on the value of the setting.
+fn constraints(setting: &SettingSchema) -> Vec<String> {
+    let mut constraints = Vec::new();
+
+    match (setting.min, setting.max) {
+        (Some(min), Some(max)) => constraints.push(format!("between {min} and {max}")),
+        (Some(min), None) => constraints.push(format!("at least {min}")),
+        (None, Some(max)) => constraints.push(format!("at most {max}")),
+        (None, None) => {}
+    }
+
+    if !setting.allowed.is_empty() {
+        let allowed: Vec<String> = setting.allowed.iter().map(scalar).collect();
+        constraints.push(format!("one of {}", allowed.join(", ")));
+    }
+
+    constraints
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Constraint, SettingType};
+
+    fn init_schema() -> Schema {
+        let mut schema = Schema::default();
+        schema
+            .add(
+                SettingSchema::new("app_name", SettingType::Text)
+                    .description("Name of the application.")
+                    .required()
+                    .validator(Validator::not_empty())
+                    .validator(Validator::max_length(32)),
+            )
+            .add(
+                SettingSchema::new("server.port", SettingType::Integer)
+                    .description("Port the server listens on.")
+                    .default_value(8080.into())
+                    .range(1, 65535)
+                    .alias("port"),
+            )
+            .add(
+                SettingSchema::new("log.level", SettingType::Text)
+                    .description("Log level | verbosity.")
+                    .allowed(vec!["debug".into(), "info".into()])
+                    .default_value("info".into()),
+            )
+            .add(
+                SettingSchema::new("server.dry_run", SettingType::Boolean)
+                    .deprecated("use 'mode' instead"),
+            )
+            .constrain(Constraint::conflicts_with("server.dry_run", &["app_name"]));
+
+        schema
+    }
+
+    #[test]
+    fn json_schema() {
+        let schema = init_schema();
+
+        assert_eq!(
+            r#"{
+  "$schema": "https://json-schema.org/draft/2020-12/schema",
+  "type": "object",
+  "properties": {
+    "app_name": {
+      "type": "string",
+      "description": "Name of the application.",
+      "minLength": 1,
+      "maxLength": 32
+    },
+    "log": {
+      "type": "object",
+      "properties": {
+        "level": {
+          "type": "string",
+          "description": "Log level | verbosity.",
+          "default": "info",
+          "enum": ["debug", "info"]
+        }
+      }
+    },
+    "server": {
+      "type": "object",
+      "properties": {
+        "dry_run": {
+          "type": "boolean",
+          "deprecated": true
+        },
+        "port": {
+          "type": "integer",
+          "description": "Port the server listens on.",
+          "default": 8080,
+          "minimum": 1,
+          "maximum": 65535
+        }
+      }
+    }
+  },
+  "required": ["app_name"]
+}
+"#,
+            SchemaDocs::new(&schema).json_schema()
+        );
+    }
+
+    #[test]
+    fn nested_setting() {
+        let mut schema = Schema::default();
+        schema
+            .add(SettingSchema::new("tls.cert", SettingType::Text).default_value("cert.pem".into()))
+            .add(SettingSchema::new("tls", SettingType::Boolean).default_value(true.into()));
+        let docs = SchemaDocs::new(&schema);
+
+        let json = docs.json_schema();
+        assert!(
+            json.contains("    \"tls\": {\n      \"type\": \"boolean\""),
+            "{json}"
+        );
+        assert!(
+            json.contains("    \"tls.cert\": {\n      \"type\": \"string\""),
+            "{json}"
+        );
+
+        let sample = docs.sample_toml();
+        assert!(!sample.contains("[tls]"), "{sample}");
+        assert!(
+            sample.contains("\n\"tls.cert\" = \"cert.pem\"\n"),
+            "{sample}"
+        );
+
+        #[cfg(feature = "toml")]
+        {
+            use crate::pool::FlatPool;
+
+            let pool = crate::FileFormat::Toml
+                .parse("sample.toml", &sample)
+                .unwrap();
+            assert_eq!(vec!["tls", "tls.cert"], pool.keys());
+        }
+    }
+
+    #[test]
+    fn names() {
+        let schema = init_schema();
+        let names = SchemaDocs::new(&schema).env_prefix("APP_").names();
+
+        assert_eq!(
+            SettingNames {
+                key: "server.dry_run".to_string(),
+                env_var: "APP_SERVER__DRY_RUN".to_string(),
+                cli_flag: "--server.dry-run".to_string(),
+            },
+            names[3]
+        );
+    }
+
+    #[test]
+    fn markdown() {
+        let schema = init_schema();
+        let markdown = SchemaDocs::new(&schema).env_prefix("APP_").markdown();
+        let lines: Vec<&str> = markdown.lines().collect();
+
+        assert_eq!(10, lines.len());
+        assert_eq!(
+            "| `server.port` | integer | `8080` | no | `APP_SERVER__PORT` | `--server.port` | Port the server listens on. Between 1 and 65535. Deprecated aliases: `port`. |",
+            lines[3]
+        );
+        assert_eq!(
+            "| `log.level` | text | `\"info\"` | no | `APP_LOG__LEVEL` | `--log.level` | Log level \\| verbosity. One of \"debug\", \"info\". |",
+            lines[4]
+        );
+        assert!(lines[2]
+            .ends_with("| Name of the application. Rules: `not_empty`, `max_length(32)`. |"));
+        assert!(lines[5].ends_with("| **Deprecated**: use 'mode' instead |"));
+        assert_eq!("- 'server.dry_run' conflicts with 'app_name'", lines[9]);
+    }
+
+    #[test]
+    fn sample_toml() {
+        let schema = init_schema();
+        let sample = SchemaDocs::new(&schema).sample_toml();
+
+        assert_eq!(
+            r#"# Name of the application.
+# Type: text, required
+# Rules: not_empty, max_length(32)
+# Environment: APP_NAME, flag: --app-name
+# app_name =
+
+[log]
+# Log level | verbosity.
+# Type: text, one of "debug", "info"
+# Environment: LOG__LEVEL, flag: --log.level
+level = "info"
+
+[server]
+# Port the server listens on.
+# Type: integer, between 1 and 65535
+# Environment: SERVER__PORT, flag: --server.port
+port = 8080
+
+# Type: boolean
+# Environment: SERVER__DRY_RUN, flag: --server.dry-run
+# Deprecated: use 'mode' instead
+# dry_run =
+"#,
+            sample
+        );
+    }
+}
@@ -0,0 +1,159 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    pool::{IncludeFlatPool, Layer, LayeredFlatPool},
+    ConfigError, FileFormat,
+};
+
+/// Is the given drop-in file read? Hidden files are skipped, like editor lock files, and so are
+/// files with unknown extensions, like backups (`app.toml~`, `app.toml.bak`, `app.toml.rpmnew`…).
+fn is_drop_in(path: &Path) -> bool {
+    let hidden = path
+        .file_name()
+        .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+
+    !hidden && path.is_file() && FileFormat::from_path(path).is_some()
+}
+
+/// ## Drop-in directories
+///
+/// A main configuration file overridden by the files of a drop-in directory, like
+/// `/etc/app/config.toml` and `/etc/app/conf.d/*.toml`. Drop-ins are read in the lexical order of
+/// their names, so `50-site.toml` overrides `10-package.toml`, and may use any known
+/// [FileFormat]. Hidden files and files with unknown extensions, editor and package manager
+/// backups among them, are ignored. A missing main file or directory is skipped.
+///
+/// The drop-ins are listed when the files are loaded: the [source
+/// files](crate::pool::FlatPool::source_files) of the pool only hold the files found then, so
+/// [ReloadableConfig::watch](crate::ReloadableConfig::watch) notices the changes and removals of
+/// these files but not a drop-in added later. Call
+/// [reload](crate::ReloadableConfig::reload) to pick up new drop-ins.
+///
+/// ```rust,no_run
+/// use flat_config::DropIns;
+///
+/// let pool = DropIns::new("/etc/app/conf.d")
+///     .main_file("/etc/app/config.toml")
+///     .load()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct DropIns {
+    main_file: Option<PathBuf>,
+    dir: PathBuf,
+}
+
+impl DropIns {
+    /// Drop-ins of the given directory, without main file.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            main_file: None,
+            dir: dir.into(),
+        }
+    }
+
+    /// File overridden by the drop-ins.
+    pub fn main_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.main_file = Some(path.into());
+
+        self
+    }
+
+    /// Existing files from the lowest to the highest precedence: the main file then the drop-ins.
+    pub fn files(&self) -> Result<Vec<PathBuf>, ConfigError> {
+        let mut drop_ins = Vec::new();
+
+        if self.dir.is_dir() {
+            let error = |e: std::io::Error| ConfigError::Source {
+                origin: self.dir.display().to_string(),
+                message: e.to_string(),
+            };
+
+            for entry in fs::read_dir(&self.dir).map_err(error)? {
+                let path = entry.map_err(error)?.path();
+
+                if is_drop_in(&path) {
+                    drop_ins.push(path);
+                }
+            }
+        }
+        drop_ins.sort_by(|a, b| a.file_name().cmp(&b.file_name()));
+
+        let main_file = self.main_file.iter().filter(|path| path.exists()).cloned();
+
+        Ok(main_file.chain(drop_ins).collect())
+    }
+
+    /// Load the files, with their includes, in a layered pool.
+    pub fn load(&self) -> Result<LayeredFlatPool, ConfigError> {
+        let layers = self
+            .files()?
+            .into_iter()
+            .map(|path| Ok(Box::new(IncludeFlatPool::load(path)?) as Layer))
+            .collect::<Result<_, ConfigError>>()?;
+
+        Ok(LayeredFlatPool::new(layers))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pool::FlatPool;
+
+    fn write_file(dir: &str, name: &str, content: &str) -> PathBuf {
+        let dir = std::env::temp_dir()
+            .join(format!("flat_config_drop_in_{}", std::process::id()))
+            .join(dir);
+        let path = dir.join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, content).unwrap();
+
+        path
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn load() {
+        let main = write_file("app", "config.toml", "a = 0\nb = 0\nc = 0\nd = 0\n");
+        let package = write_file("app", "conf.d/10-package.toml", "b = 1\nc = 1\nd = 1\n");
+        let site = write_file("app", "conf.d/50-site.ini", "c = 2\nd = 2\n");
+        let local = write_file("app", "conf.d/90-local.toml", "d = 3\n");
+        for ignored in [
+            "90-local.toml~",
+            "90-local.toml.bak",
+            "90-local.toml.rpmnew",
+            ".90-local.toml.swp",
+            ".99-hidden.toml",
+            "README",
+        ] {
+            write_file("app", &format!("conf.d/{ignored}"), "d = 4\n");
+        }
+        fs::create_dir_all(main.with_file_name("conf.d/99-dir.toml")).unwrap();
+
+        let drop_ins = DropIns::new(main.with_file_name("conf.d")).main_file(&main);
+        let pool = drop_ins.load().unwrap();
+
+        assert_eq!(
+            vec![main.clone(), package, site, local],
+            drop_ins.files().unwrap()
+        );
+        for (key, value) in [("a", 0), ("b", 1), ("c", 2), ("d", 3)] {
+            assert_eq!(Some(value.into()), pool.get(key), "{key}");
+        }
+    }
+
+    #[test]
+    fn missing() {
+        let drop_ins = DropIns::new("/unexistent/conf.d").main_file("/unexistent/config.toml");
+
+        assert!(drop_ins.files().unwrap().is_empty());
+        assert!(drop_ins.load().unwrap().keys().is_empty());
+
+        let broken = write_file("broken", "conf.d/10-broken.toml", "broken");
+        assert!(DropIns::new(broken.parent().unwrap()).load().is_err());
+    }
+}
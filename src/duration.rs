@@ -0,0 +1,234 @@
+use std::time::Duration;
+
+use crate::{ConfigError, FlatValue, TryUnwrap};
+
+/// ## Duration unit
+///
+/// Unit used to interpret integer values (or unit-less texts) as durations.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DurationUnit {
+    Milliseconds,
+    #[default]
+    Seconds,
+    Minutes,
+    Hours,
+    Days,
+}
+
+impl DurationUnit {
+    /// Number of milliseconds in one unit.
+    fn millis(&self) -> u64 {
+        match self {
+            Self::Milliseconds => 1,
+            Self::Seconds => 1_000,
+            Self::Minutes => 60_000,
+            Self::Hours => 3_600_000,
+            Self::Days => 86_400_000,
+        }
+    }
+
+    fn from_suffix(suffix: &str) -> Option<Self> {
+        match suffix {
+            "ms" => Some(Self::Milliseconds),
+            "s" => Some(Self::Seconds),
+            "m" => Some(Self::Minutes),
+            "h" => Some(Self::Hours),
+            "d" => Some(Self::Days),
+            _ => None,
+        }
+    }
+}
+
+/// Parse a human friendly duration like `250ms`, `30s`, `5m`, `1h30m` or `2d`. A text holding
+/// only digits is interpreted using the given default unit.
+fn parse_duration(text: &str, default_unit: DurationUnit) -> Result<Duration, ConfigError> {
+    let error =
+        |reason: &str| ConfigError::IncorrectValue(format!("invalid duration '{text}', {reason}"));
+    let trimmed = text.trim();
+
+    if trimmed.starts_with('-') {
+        return Err(error("durations cannot be negative"));
+    }
+
+    if trimmed.is_empty() {
+        return Err(error(
+            "expected a value like '250ms', '30s', '5m', '1h30m' or '2d'",
+        ));
+    }
+
+    if trimmed.chars().all(|c| c.is_ascii_digit()) {
+        return trimmed
+            .parse::<u64>()
+            .ok()
+            .and_then(|amount| amount.checked_mul(default_unit.millis()))
+            .map(Duration::from_millis)
+            .ok_or_else(|| error("the value is too large"));
+    }
+
+    let mut total: u64 = 0;
+    let mut rest = trimmed;
+
+    while !rest.is_empty() {
+        let digits_end = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+
+        if digits_end == 0 {
+            return Err(error("expected a number before each unit"));
+        }
+
+        let amount: u64 = rest[..digits_end]
+            .parse()
+            .map_err(|_| error("the value is too large"))?;
+        rest = &rest[digits_end..];
+
+        let unit_end = rest
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let suffix = &rest[..unit_end];
+        let unit = DurationUnit::from_suffix(suffix).ok_or_else(|| {
+            error(&format!(
+                "unknown unit '{suffix}' (accepted units are 'ms', 's', 'm', 'h', 'd')"
+            ))
+        })?;
+        rest = &rest[unit_end..];
+
+        total = amount
+            .checked_mul(unit.millis())
+            .and_then(|millis| total.checked_add(millis))
+            .ok_or_else(|| error("the value is too large"))?;
+    }
+
+    Ok(Duration::from_millis(total))
+}
+
+/// Shortest human friendly form of the given duration using a single unit, sub-millisecond
+/// precision is lost.
+pub(crate) fn format_duration(duration: &Duration) -> String {
+    let millis = duration.as_millis();
+
+    for (suffix, unit) in [
+        ("d", DurationUnit::Days),
+        ("h", DurationUnit::Hours),
+        ("m", DurationUnit::Minutes),
+        ("s", DurationUnit::Seconds),
+    ] {
+        let unit_millis = u128::from(unit.millis());
+
+        if millis > 0 && millis.is_multiple_of(unit_millis) {
+            return format!("{}{suffix}", millis / unit_millis);
+        }
+    }
+
+    format!("{millis}ms")
+}
+
+impl FlatValue {
+    /// Convert the value as a [Duration]. Texts are parsed as human friendly durations (`250ms`,
+    /// `30s`, `5m`, `1h30m`, `2d`) and integers are expressed in the given default unit.
+    pub fn try_unwrap_duration(&self, default_unit: DurationUnit) -> Result<Duration, ConfigError> {
+        match self {
+            Self::Integer(i) => {
+                let amount = u64::try_from(*i).map_err(|_| {
+                    ConfigError::IncorrectValue(format!(
+                        "invalid duration '{i}', durations cannot be negative"
+                    ))
+                })?;
+                let millis = amount.checked_mul(default_unit.millis()).ok_or_else(|| {
+                    ConfigError::IncorrectValue(format!(
+                        "invalid duration '{i}', the value is too large"
+                    ))
+                })?;
+
+                Ok(Duration::from_millis(millis))
+            }
+            Self::Text(t) => parse_duration(t, default_unit),
+            _ => Err(ConfigError::TypeMismatch {
+                expected: "duration".to_string(),
+                present: self.display(),
+            }),
+        }
+    }
+}
+
+/// Integer values are expressed in seconds, use [FlatValue::try_unwrap_duration] to choose
+/// another unit.
+impl TryUnwrap<Duration> for FlatValue {
+    fn try_unwrap(&self) -> Result<Duration, ConfigError> {
+        self.try_unwrap_duration(DurationUnit::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Result<Duration, ConfigError> {
+        FlatValue::from(text).try_unwrap()
+    }
+
+    #[test]
+    fn simple_units() {
+        assert_eq!(Duration::from_millis(250), parse("250ms").unwrap());
+        assert_eq!(Duration::from_secs(30), parse("30s").unwrap());
+        assert_eq!(Duration::from_secs(300), parse("5m").unwrap());
+        assert_eq!(Duration::from_secs(3_600), parse("1h").unwrap());
+        assert_eq!(Duration::from_secs(172_800), parse("2d").unwrap());
+    }
+
+    #[test]
+    fn compound_units() {
+        assert_eq!(Duration::from_secs(5_400), parse("1h30m").unwrap());
+        assert_eq!(Duration::from_millis(61_500), parse("1m1s500ms").unwrap());
+    }
+
+    #[test]
+    fn default_unit() {
+        let value = FlatValue::Integer(3);
+
+        assert_eq!(Duration::from_secs(3), value.try_unwrap().unwrap());
+        assert_eq!(
+            Duration::from_millis(3),
+            value
+                .try_unwrap_duration(DurationUnit::Milliseconds)
+                .unwrap()
+        );
+        assert_eq!(
+            Duration::from_secs(180),
+            FlatValue::from("3")
+                .try_unwrap_duration(DurationUnit::Minutes)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn negative() {
+        assert!(matches!(parse("-5s"), Err(ConfigError::IncorrectValue(_))));
+
+        let result: Result<Duration, ConfigError> = FlatValue::Integer(-1).try_unwrap();
+        assert!(matches!(result, Err(ConfigError::IncorrectValue(_))));
+    }
+
+    #[test]
+    fn overflow() {
+        assert!(matches!(
+            parse("99999999999999999999d"),
+            Err(ConfigError::IncorrectValue(_))
+        ));
+        assert!(matches!(
+            parse("9999999999999999d"),
+            Err(ConfigError::IncorrectValue(_))
+        ));
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(parse("").is_err());
+        assert!(parse("5 minutes").is_err());
+        assert!(parse("ms").is_err());
+        assert!(parse("1h30").is_err());
+
+        let result: Result<Duration, ConfigError> = FlatValue::Boolean(true).try_unwrap();
+        assert!(matches!(result, Err(ConfigError::TypeMismatch { .. })));
+    }
+}
@@ -1,6 +1,6 @@
 use std::{error::Error, fmt::Display};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum ConfigError {
     /// Configuration setting named is missing.
     Missing { field_name: String, fields: String },
@@ -11,23 +11,90 @@ pub enum ConfigError {
     /// The value is incorrect, give a useful context error message (field name, why the value was
     /// wrong or what was expected.
     IncorrectValue(String),
+
+    /// The value does not satisfy the named validation rule.
+    Rule { rule: String, value: String },
+
+    /// Several settings are inconsistent with each other.
+    Constraint { keys: Vec<String>, message: String },
+
+    /// A configuration source (file, directory…) could not be read or parsed.
+    Source { origin: String, message: String },
+
+    /// The given error occurred while reading the named setting.
+    Setting {
+        key: String,
+        error: Box<ConfigError>,
+    },
+
+    /// Several settings are invalid, all the errors are reported at once.
+    Invalid(Vec<ConfigError>),
 }
 
-impl Display for ConfigError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl ConfigError {
+    /// Attach the name of the setting this error is about, unless the error already names it.
+    pub fn with_key(self, key: &str) -> Self {
         match self {
-            Self::Missing { field_name, fields } => write!(f, "CONFIGURATION ERROR: Field '{field_name}' is missing. Available fields are: '{fields}'."),
+            Self::Missing { .. } | Self::Setting { .. } => self,
+            error => Self::Setting {
+                key: key.to_string(),
+                error: Box::new(error),
+            },
+        }
+    }
+
+    fn describe(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Missing { field_name, fields } => write!(
+                f,
+                "Field '{field_name}' is missing. Available fields are: '{fields}'."
+            ),
             Self::TypeMismatch { expected, present } => {
-                write!(
-                    f,
-                    "CONFIGURATION ERROR: Type mismatch, expected '{expected}' got '{present}'."
-                )
+                write!(f, "Type mismatch, expected '{expected}' got '{present}'.")
             }
             Self::IncorrectValue(message) => {
-                write!(f, "CONFIGURATION ERROR: Incorrect value: {message}.")
+                write!(f, "Incorrect value: {message}.")
+            }
+            Self::Rule { rule, value } => {
+                write!(f, "Value {value} does not satisfy the rule {rule}.")
+            }
+            Self::Constraint { keys, message } => {
+                let keys: Vec<String> = keys.iter().map(|key| format!("'{key}'")).collect();
+                write!(f, "Settings {}: {message}.", keys.join(", "))
+            }
+            Self::Source { origin, message } => {
+                write!(f, "Could not load '{origin}': {message}.")
+            }
+            Self::Setting { key, error } => {
+                write!(f, "Setting '{key}': ")?;
+                error.describe(f)
+            }
+            Self::Invalid(errors) => {
+                write!(f, "{} invalid settings:", errors.len())?;
+
+                for error in errors {
+                    write!(f, "\n  - ")?;
+                    error.describe(f)?;
+                }
+
+                Ok(())
             }
         }
     }
 }
 
-impl Error for ConfigError {}
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "CONFIGURATION ERROR: ")?;
+        self.describe(f)
+    }
+}
+
+impl Error for ConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Setting { error, .. } => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+}
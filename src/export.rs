@@ -0,0 +1,437 @@
+use std::fmt::Write;
+
+use crate::{nested, pool::FlatPool, FlatValue, REDACTED};
+
+/// ## Export formats
+///
+/// Formats the settings of a pool can be rendered in. Hierarchical formats (TOML, JSON) turn
+/// dotted keys back into nested tables, flat formats (`.env`, shell) turn keys into environment
+/// variable names (`database.port` becomes `DATABASE__PORT`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Toml,
+    Json,
+    Dotenv,
+    Shell,
+}
+
+/// ## Exporter
+///
+/// Render the effective settings of a pool, keys are sorted so the output can be diffed and secret
+/// values are redacted.
+///
+/// ```rust
+/// use flat_config::{pool::SimpleFlatPool, ExportFormat, Exporter};
+///
+/// let mut pool = SimpleFlatPool::default();
+/// pool.add("database.port", 5432.into())
+///     .add("app_name", "whatever".into());
+///
+/// assert_eq!(
+///     "app_name = \"whatever\"\n\n[database]\nport = 5432\n",
+///     Exporter::new(ExportFormat::Toml).export(&pool)
+/// );
+/// assert_eq!(
+///     "export APP_APP_NAME='whatever'\nexport APP_DATABASE__PORT=5432\n",
+///     Exporter::new(ExportFormat::Shell).env_prefix("APP_").export(&pool)
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct Exporter {
+    format: ExportFormat,
+    env_prefix: String,
+}
+
+impl Exporter {
+    pub fn new(format: ExportFormat) -> Self {
+        Self {
+            format,
+            env_prefix: String::new(),
+        }
+    }
+
+    /// Prefix added to environment variable names in the flat formats.
+    pub fn env_prefix(mut self, prefix: &str) -> Self {
+        self.env_prefix = prefix.to_string();
+
+        self
+    }
+
+    /// Render all the settings of the given pool.
+    pub fn export(&self, pool: &(impl FlatPool + ?Sized)) -> String {
+        let settings: Vec<(String, FlatValue)> = pool
+            .keys()
+            .into_iter()
+            .filter_map(|key| {
+                let value = if pool.is_secret(&key) {
+                    Some(FlatValue::from(REDACTED))
+                } else {
+                    pool.get(&key)
+                };
+
+                value.map(|value| (key, value))
+            })
+            .collect();
+
+        match self.format {
+            ExportFormat::Toml => render_toml(&Table::nest(settings).0),
+            ExportFormat::Json => {
+                let mut output = String::new();
+                render_json(&Node::Table(Table::nest(settings).0), 0, &mut output);
+                output.push('\n');
+
+                output
+            }
+            ExportFormat::Dotenv => settings
+                .iter()
+                .map(|(key, value)| {
+                    format!(
+                        "{}={}\n",
+                        env_var_name(&self.env_prefix, key),
+                        dotenv_value(value)
+                    )
+                })
+                .collect(),
+            ExportFormat::Shell => settings
+                .iter()
+                .map(|(key, value)| {
+                    format!(
+                        "export {}={}\n",
+                        env_var_name(&self.env_prefix, key),
+                        shell_value(value)
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Environment variable name of the given setting: upper case with `.` replaced by `__` and `-`
+/// by `_`, `server.dry_run` is `SERVER__DRY_RUN`. [EnvFlatPool](crate::pool::EnvFlatPool) reads
+/// the name back as the key with `_` in place of `-`.
+pub fn env_var_name(prefix: &str, key: &str) -> String {
+    let name = key
+        .replace('.', "__")
+        .replace('-', "_")
+        .to_ascii_uppercase();
+
+    format!("{prefix}{name}")
+}
+
+/// Command line flag of the given setting: lower case with `_` replaced by `-`, `server.dry_run`
+/// is `--server.dry-run`. [ArgsFlatPool](crate::pool::ArgsFlatPool) reads the flag back as the key
+/// with `_` in place of `-`.
+pub fn cli_flag_name(key: &str) -> String {
+    let name = key.replace('_', "-").to_ascii_lowercase();
+
+    format!("--{name}")
+}
+
+type Node = nested::Node<FlatValue>;
+type Table = nested::Table<FlatValue>;
+
+pub(crate) fn quote(text: &str) -> String {
+    let mut quoted = String::from("\"");
+
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(quoted, "\\u{:04x}", c as u32);
+            }
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+
+    quoted
+}
+
+pub(crate) fn scalar(value: &FlatValue) -> String {
+    match value {
+        FlatValue::Integer(i) => i.to_string(),
+        FlatValue::Text(t) => quote(t),
+        FlatValue::Boolean(b) => b.to_string(),
+    }
+}
+
+pub(crate) fn toml_key(key: &str) -> String {
+    let bare = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+
+    if bare {
+        key.to_string()
+    } else {
+        quote(key)
+    }
+}
+
+fn toml_inline(node: &Node) -> String {
+    match node {
+        Node::Value(value) => scalar(value),
+        Node::Table(table) if table.is_scalar_array() => {
+            let elements: Vec<String> = table.elements().into_iter().map(toml_inline).collect();
+
+            format!("[{}]", elements.join(", "))
+        }
+        Node::Table(table) => {
+            let entries: Vec<String> = table
+                .0
+                .iter()
+                .map(|(key, node)| format!("{} = {}", toml_key(key), toml_inline(node)))
+                .collect();
+
+            format!("{{ {} }}", entries.join(", "))
+        }
+    }
+}
+
+fn render_toml(root: &Table) -> String {
+    let mut sections: Vec<String> = Vec::new();
+    toml_section(root, &[], &mut sections);
+
+    sections.join("\n")
+}
+
+/// Render the scalar values of the table under its header then its sub tables.
+fn toml_section(table: &Table, path: &[String], sections: &mut Vec<String>) {
+    let mut section = String::new();
+    let mut sub_tables = Vec::new();
+
+    for (key, node) in &table.0 {
+        match node {
+            Node::Table(sub_table) if !sub_table.is_scalar_array() => {
+                sub_tables.push((key, sub_table))
+            }
+            node => {
+                let _ = writeln!(section, "{} = {}", toml_key(key), toml_inline(node));
+            }
+        }
+    }
+
+    if !section.is_empty() {
+        if !path.is_empty() {
+            let header: Vec<String> = path.iter().map(|key| toml_key(key)).collect();
+            section.insert_str(0, &format!("[{}]\n", header.join(".")));
+        }
+        sections.push(section);
+    }
+
+    for (key, sub_table) in sub_tables {
+        let mut sub_path = path.to_vec();
+        sub_path.push(key.to_string());
+        toml_section(sub_table, &sub_path, sections);
+    }
+}
+
+fn render_json(node: &Node, depth: usize, output: &mut String) {
+    let indent = "  ".repeat(depth + 1);
+
+    match node {
+        Node::Value(value) => output.push_str(&scalar(value)),
+        Node::Table(table) if table.0.is_empty() => output.push_str("{}"),
+        Node::Table(table) if table.is_array() => {
+            output.push_str("[\n");
+
+            for (index, element) in table.elements().into_iter().enumerate() {
+                if index > 0 {
+                    output.push_str(",\n");
+                }
+                output.push_str(&indent);
+                render_json(element, depth + 1, output);
+            }
+            let _ = write!(output, "\n{}]", "  ".repeat(depth));
+        }
+        Node::Table(table) => {
+            output.push_str("{\n");
+
+            for (index, (key, node)) in table.0.iter().enumerate() {
+                if index > 0 {
+                    output.push_str(",\n");
+                }
+                let _ = write!(output, "{indent}{}: ", quote(key));
+                render_json(node, depth + 1, output);
+            }
+            let _ = write!(output, "\n{}}}", "  ".repeat(depth));
+        }
+    }
+}
+
+fn dotenv_value(value: &FlatValue) -> String {
+    match value {
+        FlatValue::Text(t) => {
+            let mut quoted = String::from("\"");
+
+            for c in t.chars() {
+                match c {
+                    '"' | '\\' | '$' | '`' => {
+                        quoted.push('\\');
+                        quoted.push(c);
+                    }
+                    '\n' => quoted.push_str("\\n"),
+                    '\r' => quoted.push_str("\\r"),
+                    '\t' => quoted.push_str("\\t"),
+                    c if c.is_control() => {
+                        let _ = write!(quoted, "\\u{:04x}", c as u32);
+                    }
+                    c => quoted.push(c),
+                }
+            }
+            quoted.push('"');
+
+            quoted
+        }
+        value => scalar(value),
+    }
+}
+
+fn shell_value(value: &FlatValue) -> String {
+    match value {
+        FlatValue::Text(t) => format!("'{}'", t.replace('\'', "'\\''")),
+        value => scalar(value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pool::{LayeredFlatPool, SimpleFlatPool};
+
+    fn init_pool() -> SimpleFlatPool {
+        let mut pool = SimpleFlatPool::default();
+        pool.add("app_name", "what \"ever\"".into())
+            .add("dry_run", false.into())
+            .add("database.host", "localhost".into())
+            .add("database.port", 5432.into())
+            .add("database.replica.host", "replica".into())
+            .add("servers.0", "a".into())
+            .add("servers.1", "b".into());
+
+        pool
+    }
+
+    #[test]
+    fn toml() {
+        let output = Exporter::new(ExportFormat::Toml).export(&init_pool());
+
+        assert_eq!(
+            r#"app_name = "what \"ever\""
+dry_run = false
+servers = ["a", "b"]
+
+[database]
+host = "localhost"
+port = 5432
+
+[database.replica]
+host = "replica"
+"#,
+            output
+        );
+    }
+
+    #[test]
+    fn json() {
+        let output = Exporter::new(ExportFormat::Json).export(&init_pool());
+
+        assert_eq!(
+            r#"{
+  "app_name": "what \"ever\"",
+  "database": {
+    "host": "localhost",
+    "port": 5432,
+    "replica": {
+      "host": "replica"
+    }
+  },
+  "dry_run": false,
+  "servers": [
+    "a",
+    "b"
+  ]
+}
+"#,
+            output
+        );
+    }
+
+    #[test]
+    fn dotenv() {
+        let output = Exporter::new(ExportFormat::Dotenv).export(&init_pool());
+
+        assert_eq!(
+            r#"APP_NAME="what \"ever\""
+DATABASE__HOST="localhost"
+DATABASE__PORT=5432
+DATABASE__REPLICA__HOST="replica"
+DRY_RUN=false
+SERVERS__0="a"
+SERVERS__1="b"
+"#,
+            output
+        );
+    }
+
+    #[test]
+    fn dotenv_control_characters() {
+        let mut pool = SimpleFlatPool::default();
+        pool.add("banner", "a\r\nb\tc\u{7}d".into());
+        let output = Exporter::new(ExportFormat::Dotenv).export(&pool);
+
+        assert_eq!("BANNER=\"a\\r\\nb\\tc\\u0007d\"\n", output);
+    }
+
+    #[test]
+    fn shell() {
+        let mut pool = SimpleFlatPool::default();
+        pool.add("motto", "it's fine".into())
+            .add("log-level", 2.into());
+        let output = Exporter::new(ExportFormat::Shell)
+            .env_prefix("APP_")
+            .export(&pool);
+
+        assert_eq!(
+            "export APP_LOG_LEVEL=2\nexport APP_MOTTO='it'\\''s fine'\n",
+            output
+        );
+    }
+
+    #[test]
+    fn layered() {
+        let mut parameter_pool = SimpleFlatPool::default();
+        parameter_pool.add("database.port", 6432.into());
+        let pool = LayeredFlatPool::new(vec![Box::new(init_pool()), Box::new(parameter_pool)]);
+        let output = Exporter::new(ExportFormat::Dotenv).export(&pool);
+
+        assert!(output.contains("DATABASE__PORT=6432\n"));
+        assert!(!output.contains("5432"));
+    }
+
+    #[test]
+    fn secrets() {
+        let mut pool = init_pool();
+        pool.add_secret("database.password", "hunter2".into());
+        let output = Exporter::new(ExportFormat::Dotenv).export(&pool);
+
+        assert!(output.contains("DATABASE__PASSWORD=\"***\"\n"));
+        assert!(!output.contains("hunter2"));
+    }
+
+    #[test]
+    fn conflicting_keys() {
+        let mut pool = SimpleFlatPool::default();
+        pool.add("tls", true.into())
+            .add("tls.cert", "cert.pem".into());
+
+        assert_eq!(
+            "tls = true\n\"tls.cert\" = \"cert.pem\"\n",
+            Exporter::new(ExportFormat::Toml).export(&pool)
+        );
+    }
+}
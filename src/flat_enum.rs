@@ -0,0 +1,195 @@
+use crate::{ConfigError, FlatValue, TryUnwrap};
+
+/// ## Enum choice
+///
+/// One accepted value of an enum setting with all its spellings. The first name is the canonical
+/// one, the others are aliases.
+#[derive(Debug, Clone)]
+pub struct Choice<T> {
+    value: T,
+    names: Vec<&'static str>,
+    numbers: Vec<isize>,
+}
+
+impl<T> Choice<T> {
+    pub fn new(value: T, name: &'static str) -> Self {
+        Self {
+            value,
+            names: vec![name],
+            numbers: Vec::new(),
+        }
+    }
+
+    /// Add an alternative spelling for this choice.
+    pub fn alias(mut self, name: &'static str) -> Self {
+        self.names.push(name);
+
+        self
+    }
+
+    /// Accept the given integer for this choice.
+    pub fn number(mut self, number: isize) -> Self {
+        self.numbers.push(number);
+
+        self
+    }
+
+    /// Canonical name of this choice.
+    pub fn name(&self) -> &'static str {
+        self.names[0]
+    }
+
+    fn matches(&self, value: &FlatValue) -> bool {
+        match value {
+            FlatValue::Text(t) => self
+                .names
+                .iter()
+                .any(|name| name.eq_ignore_ascii_case(t.trim())),
+            FlatValue::Integer(i) => self.numbers.contains(i),
+            _ => false,
+        }
+    }
+
+    fn spellings(&self) -> impl Iterator<Item = String> + '_ {
+        self.names
+            .iter()
+            .map(|name| format!("'{name}'"))
+            .chain(self.numbers.iter().map(|number| number.to_string()))
+    }
+}
+
+/// ## Enum settings
+///
+/// Implement this trait to read enum values from flat values. Text values are matched case
+/// insensitively against the names of the choices, integer values against their numbers.
+///
+/// ```rust
+/// use flat_config::{Choice, FlatEnum, FlatValue, TryUnwrap};
+///
+/// #[derive(Debug, Clone, PartialEq)]
+/// enum LogFormat {
+///     Json,
+///     Text,
+///     Pretty,
+/// }
+///
+/// impl FlatEnum for LogFormat {
+///     fn choices() -> Vec<Choice<Self>> {
+///         vec![
+///             Choice::new(Self::Json, "json").alias("structured"),
+///             Choice::new(Self::Text, "text").alias("plain"),
+///             Choice::new(Self::Pretty, "pretty"),
+///         ]
+///     }
+/// }
+///
+/// let format: LogFormat = FlatValue::from("JSON").try_unwrap().unwrap();
+/// assert_eq!(LogFormat::Json, format);
+/// assert_eq!(Some("pretty"), LogFormat::Pretty.name());
+/// ```
+pub trait FlatEnum: Sized + Clone + PartialEq {
+    /// All the accepted choices for this enum.
+    fn choices() -> Vec<Choice<Self>>;
+
+    /// Canonical name of this value if it is declared in the choices.
+    fn name(&self) -> Option<&'static str> {
+        Self::choices()
+            .into_iter()
+            .find(|choice| &choice.value == self)
+            .map(|choice| choice.name())
+    }
+}
+
+impl<T: FlatEnum> TryUnwrap<T> for FlatValue {
+    fn try_unwrap(&self) -> Result<T, ConfigError> {
+        let choices = T::choices();
+
+        if let Some(choice) = choices.iter().find(|choice| choice.matches(self)) {
+            return Ok(choice.value.clone());
+        }
+
+        let accepted = choices
+            .iter()
+            .flat_map(|choice| choice.spellings())
+            .collect::<Vec<String>>()
+            .join(", ");
+        let present = match self {
+            Self::Text(t) => format!("'{t}'"),
+            Self::Integer(i) => i.to_string(),
+            Self::Boolean(_) => {
+                return Err(ConfigError::TypeMismatch {
+                    expected: "enumeration".to_string(),
+                    present: self.display(),
+                })
+            }
+        };
+
+        Err(ConfigError::IncorrectValue(format!(
+            "invalid value {present}, accepted values are: {accepted}"
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum LogFormat {
+        Json,
+        Text,
+        Pretty,
+    }
+
+    impl FlatEnum for LogFormat {
+        fn choices() -> Vec<Choice<Self>> {
+            vec![
+                Choice::new(Self::Json, "json").number(0),
+                Choice::new(Self::Text, "text").alias("plain").number(1),
+                Choice::new(Self::Pretty, "pretty").number(2),
+            ]
+        }
+    }
+
+    #[test]
+    fn from_names() {
+        let format: LogFormat = FlatValue::from("json").try_unwrap().unwrap();
+        assert_eq!(LogFormat::Json, format);
+
+        let format: LogFormat = FlatValue::from("Pretty").try_unwrap().unwrap();
+        assert_eq!(LogFormat::Pretty, format);
+
+        let format: LogFormat = FlatValue::from("PLAIN").try_unwrap().unwrap();
+        assert_eq!(LogFormat::Text, format);
+    }
+
+    #[test]
+    fn from_numbers() {
+        let format: LogFormat = FlatValue::Integer(2).try_unwrap().unwrap();
+        assert_eq!(LogFormat::Pretty, format);
+    }
+
+    #[test]
+    fn mismatch() {
+        let result: Result<LogFormat, ConfigError> = FlatValue::from("xml").try_unwrap();
+        let message = format!("{}", result.unwrap_err());
+
+        assert!(message.contains("'xml'"));
+        assert!(message.contains("'json', 0, 'text', 'plain', 1, 'pretty', 2"));
+
+        let result: Result<LogFormat, ConfigError> = FlatValue::Integer(5).try_unwrap();
+        assert!(matches!(result, Err(ConfigError::IncorrectValue(_))));
+
+        let result: Result<LogFormat, ConfigError> = FlatValue::Boolean(true).try_unwrap();
+        assert!(matches!(
+            result,
+            Err(ConfigError::TypeMismatch { ref expected, ref present })
+                if expected == "enumeration" && present == "boolean"
+        ));
+    }
+
+    #[test]
+    fn canonical_name() {
+        assert_eq!(Some("text"), LogFormat::Text.name());
+    }
+}
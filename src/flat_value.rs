@@ -10,7 +10,7 @@ pub enum FlatValue {
 }
 
 impl FlatValue {
-    fn display(&self) -> String {
+    pub(crate) fn display(&self) -> String {
         let subtype: &str = match self {
             Self::Integer(_) => "integer",
             Self::Text(_) => "text",
@@ -19,6 +19,15 @@ impl FlatValue {
 
         subtype.to_string()
     }
+
+    /// Value as it would be written in a configuration file, texts are quoted.
+    pub(crate) fn literal(&self) -> String {
+        match self {
+            Self::Integer(i) => i.to_string(),
+            Self::Text(t) => format!("{t:?}"),
+            Self::Boolean(b) => b.to_string(),
+        }
+    }
 }
 
 impl Display for FlatValue {
@@ -71,6 +80,28 @@ impl TryUnwrap<bool> for FlatValue {
     }
 }
 
+/// Fixed size integers, values out of the range of the type are incorrect.
+macro_rules! try_unwrap_integer {
+    ($($integer:ty),*) => {
+        $(
+            impl TryUnwrap<$integer> for FlatValue {
+                fn try_unwrap(&self) -> Result<$integer, ConfigError> {
+                    let value: isize = self.try_unwrap()?;
+
+                    <$integer>::try_from(value).map_err(|_| {
+                        ConfigError::IncorrectValue(format!(
+                            "integer {value} is out of the {} range",
+                            stringify!($integer)
+                        ))
+                    })
+                }
+            }
+        )*
+    };
+}
+
+try_unwrap_integer!(i8, i16, i32, i64, u8, u16, u32, u64, usize);
+
 impl From<isize> for FlatValue {
     fn from(value: isize) -> Self {
         Self::Integer(value)
@@ -106,7 +137,7 @@ mod tests {
     fn from_str() {
         let value: FlatValue = "whatever".into();
 
-        assert!(matches!(value, FlatValue::Text(v) if v == "whatever".to_string()));
+        assert!(matches!(value, FlatValue::Text(v) if v == "whatever"));
     }
 
     // FlatValue has to implement From<bool>
@@ -143,4 +174,20 @@ mod tests {
 
         assert!(original)
     }
+
+    // TryUnwrap for fixed size integers
+    #[test]
+    fn try_unwrap_integers() {
+        let value: FlatValue = 8080_isize.into();
+        let port: u16 = value.try_unwrap().unwrap();
+        let small: Result<u8, ConfigError> = value.try_unwrap();
+        let negative: Result<u32, ConfigError> = FlatValue::Integer(-1).try_unwrap();
+
+        assert_eq!(8080, port);
+        assert_eq!(
+            "CONFIGURATION ERROR: Incorrect value: integer 8080 is out of the u8 range.",
+            small.unwrap_err().to_string()
+        );
+        assert!(negative.is_err());
+    }
 }
@@ -0,0 +1,382 @@
+use std::path::Path;
+
+use crate::{pool::SimpleFlatPool, ConfigError, FlatValue, KeyPath};
+
+/// ## File formats
+///
+/// Formats configuration files can be read from.
+///
+/// Keys must be valid [KeyPath]s and are stored in their canonical form.
+///
+///  * `Ini`: `key = value` lines grouped in `[section]` tables whose name prefixes the keys
+///    (`port` in the `[database]` section is `database.port`). Lines starting with `#` or `;` are
+///    comments. Values are booleans (`true`, `false`), integers, double quoted texts (with `\"`,
+///    `\\`, `\n` and `\t` escapes) or bare texts.
+///  * `Toml`: TOML files, read with the `toml` feature (enabled by default). Tables prefix the
+///    keys and array elements get indexed keys (`ports = [80, 443]` gives `ports.0` and
+///    `ports.1`, `[[servers]]` gives `servers.0.host`…). Floats and dates are kept as texts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileFormat {
+    Ini,
+    Toml,
+}
+
+impl FileFormat {
+    /// Guess the format of a file from its extension.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "ini" | "conf" | "cfg" | "properties" => Some(Self::Ini),
+            "toml" => Some(Self::Toml),
+            _ => None,
+        }
+    }
+
+    /// Parse the given content, `origin` names the content in error messages.
+    pub fn parse(&self, origin: &str, content: &str) -> Result<SimpleFlatPool, ConfigError> {
+        let pool = match self {
+            Self::Ini => parse_ini(content),
+            Self::Toml => parse_toml(content),
+        };
+
+        pool.map_err(|message| ConfigError::Source {
+            origin: origin.to_string(),
+            message,
+        })
+    }
+}
+
+fn parse_ini(content: &str) -> Result<SimpleFlatPool, String> {
+    let mut pool = SimpleFlatPool::default();
+    let mut section = String::new();
+
+    for (index, line) in content.lines().enumerate() {
+        let error = |reason: &str| format!("line {}: {reason}", index + 1);
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[') {
+            section = name
+                .strip_suffix(']')
+                .ok_or_else(|| error("unclosed section header"))?
+                .trim()
+                .to_string();
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| error("expected 'key = value'"))?;
+        let key = key.trim();
+
+        if key.is_empty() {
+            return Err(error("empty key"));
+        }
+
+        let key = if section.is_empty() {
+            key.to_string()
+        } else {
+            format!("{section}.{key}")
+        };
+        let key = parse_key(&key).map_err(|reason| error(&reason))?;
+        let value = parse_ini_value(value.trim()).map_err(|reason| error(&reason))?;
+        pool.add(&key, value);
+    }
+
+    Ok(pool)
+}
+
+fn parse_ini_value(raw: &str) -> Result<FlatValue, String> {
+    if let Some(quoted) = raw.strip_prefix('"') {
+        let mut text = String::new();
+        let mut chars = quoted.chars();
+
+        loop {
+            match chars.next() {
+                None => return Err("unterminated quoted text".to_string()),
+                Some('"') => break,
+                Some('\\') => match chars.next() {
+                    Some('n') => text.push('\n'),
+                    Some('t') => text.push('\t'),
+                    Some(c @ ('"' | '\\')) => text.push(c),
+                    Some(c) => return Err(format!("unknown escape sequence '\\{c}'")),
+                    None => return Err("unterminated quoted text".to_string()),
+                },
+                Some(c) => text.push(c),
+            }
+        }
+
+        if !chars.as_str().trim().is_empty() {
+            return Err("unexpected text after the closing quote".to_string());
+        }
+
+        return Ok(FlatValue::Text(text));
+    }
+
+    Ok(match raw {
+        "true" => FlatValue::Boolean(true),
+        "false" => FlatValue::Boolean(false),
+        raw => raw
+            .parse::<isize>()
+            .map(FlatValue::Integer)
+            .unwrap_or_else(|_| FlatValue::from(raw)),
+    })
+}
+
+/// Validate the key of a setting read from a file and return its canonical form. The key may end
+/// with the `@<profile>` suffix of [ProfiledFlatPool](crate::pool::ProfiledFlatPool).
+fn parse_key(key: &str) -> Result<String, String> {
+    let parse = |key| {
+        KeyPath::parse(key).map_err(|error| match error {
+            ConfigError::IncorrectValue(reason) => reason,
+            error => error.to_string(),
+        })
+    };
+
+    match key.rsplit_once('@') {
+        Some((name, profile)) => Ok(format!("{}@{}", parse(name)?, parse(profile)?)),
+        None => parse(key).map(|key| key.to_string()),
+    }
+}
+
+/// Parse a scalar written in the environment or on the command line like an INI value.
+pub(crate) fn parse_scalar(raw: &str) -> FlatValue {
+    parse_ini_value(raw.trim()).unwrap_or_else(|_| FlatValue::from(raw))
+}
+
+#[cfg(feature = "toml")]
+fn parse_toml(content: &str) -> Result<SimpleFlatPool, String> {
+    let table: toml::Table = content
+        .parse()
+        .map_err(|e: toml::de::Error| e.to_string())?;
+    let mut pool = SimpleFlatPool::default();
+
+    for (key, value) in table {
+        add_toml_value(&mut pool, key, value)?;
+    }
+
+    Ok(pool)
+}
+
+#[cfg(not(feature = "toml"))]
+fn parse_toml(_content: &str) -> Result<SimpleFlatPool, String> {
+    Err("reading TOML files requires the `toml` feature".to_string())
+}
+
+/// Add the given value under the given key, tables and arrays are flattened.
+#[cfg(feature = "toml")]
+fn add_toml_value(
+    pool: &mut SimpleFlatPool,
+    key: String,
+    value: toml::Value,
+) -> Result<(), String> {
+    let value = match value {
+        toml::Value::String(text) => FlatValue::Text(text),
+        toml::Value::Boolean(boolean) => FlatValue::Boolean(boolean),
+        toml::Value::Integer(integer) => isize::try_from(integer)
+            .map(FlatValue::Integer)
+            .map_err(|_| format!("integer '{key}' is out of range"))?,
+        toml::Value::Float(float) => FlatValue::Text(float.to_string()),
+        toml::Value::Datetime(datetime) => FlatValue::Text(datetime.to_string()),
+        toml::Value::Array(values) => {
+            for (index, value) in values.into_iter().enumerate() {
+                add_toml_value(pool, format!("{key}.{index}"), value)?;
+            }
+
+            return Ok(());
+        }
+        toml::Value::Table(table) => {
+            for (name, value) in table {
+                add_toml_value(pool, format!("{key}.{name}"), value)?;
+            }
+
+            return Ok(());
+        }
+    };
+    pool.add(&parse_key(&key)?, value);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pool::FlatPool;
+
+    #[test]
+    fn from_path() {
+        assert_eq!(
+            Some(FileFormat::Ini),
+            FileFormat::from_path(Path::new("/etc/app/app.conf"))
+        );
+        assert_eq!(None, FileFormat::from_path(Path::new("app.unknown")));
+        assert_eq!(None, FileFormat::from_path(Path::new("app")));
+    }
+
+    #[test]
+    fn ini() {
+        let content = r#"
+# Application
+app_name = whatever
+dry_run = false
+
+[database]
+port = 5432
+; comment
+password = "p=ss \"word\"\n"
+url = postgres://localhost/app#fragment
+
+[database.replica]
+host = replica
+"#;
+        let pool = FileFormat::Ini.parse("test", content).unwrap();
+
+        assert_eq!(
+            vec![
+                "app_name",
+                "database.password",
+                "database.port",
+                "database.replica.host",
+                "database.url",
+                "dry_run"
+            ],
+            pool.keys()
+        );
+        assert_eq!(Some(FlatValue::from("whatever")), pool.get("app_name"));
+        assert_eq!(Some(FlatValue::Boolean(false)), pool.get("dry_run"));
+        assert_eq!(Some(FlatValue::Integer(5432)), pool.get("database.port"));
+        assert_eq!(
+            Some(FlatValue::from("p=ss \"word\"\n")),
+            pool.get("database.password")
+        );
+        assert_eq!(
+            Some(FlatValue::from("postgres://localhost/app#fragment")),
+            pool.get("database.url")
+        );
+    }
+
+    #[test]
+    fn ini_errors() {
+        for content in [
+            "no value",
+            "[unclosed",
+            " = value",
+            "key = \"unterminated",
+            "key = \"text\" trailing",
+            "key = \"\\q\"",
+            "log level = info",
+            "servers[x] = 1",
+        ] {
+            let error = FileFormat::Ini.parse("test.ini", content).unwrap_err();
+
+            assert!(
+                matches!(error, ConfigError::Source { ref origin, ref message } if origin == "test.ini" && message.starts_with("line 1:")),
+                "{content}: {error:?}"
+            );
+        }
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn toml() {
+        let content = r#"
+# Application
+app_name = "whatever" # inline comment
+dry_run = false
+"quoted.key" = 'C:\path'
+workers = 1_000
+ratio = 0.5
+started = 1979-05-27T07:32:00Z
+motd = """
+Hello \u00e9"""
+
+[database]
+port = 5432
+password = "p=ss \"word\"\n"
+replica = { host = "replica", port = 5433 }
+ports = [
+    80,
+    443,
+]
+
+[[servers]]
+host = "alpha"
+
+[[servers]]
+host = "beta"
+
+[server.tls]
+enabled = true
+"#;
+        let pool = FileFormat::Toml.parse("test", content).unwrap();
+
+        assert_eq!(
+            vec![
+                "app_name",
+                "database.password",
+                "database.port",
+                "database.ports.0",
+                "database.ports.1",
+                "database.replica.host",
+                "database.replica.port",
+                "dry_run",
+                "motd",
+                "quoted.key",
+                "ratio",
+                "server.tls.enabled",
+                "servers.0.host",
+                "servers.1.host",
+                "started",
+                "workers",
+            ],
+            pool.keys()
+        );
+        assert_eq!(Some(FlatValue::from("whatever")), pool.get("app_name"));
+        assert_eq!(Some(FlatValue::from("C:\\path")), pool.get("quoted.key"));
+        assert_eq!(Some(FlatValue::Integer(1000)), pool.get("workers"));
+        assert_eq!(Some(FlatValue::from("0.5")), pool.get("ratio"));
+        assert_eq!(
+            Some(FlatValue::from("1979-05-27T07:32:00Z")),
+            pool.get("started")
+        );
+        assert_eq!(Some(FlatValue::from("Hello \u{e9}")), pool.get("motd"));
+        assert_eq!(
+            Some(FlatValue::from("p=ss \"word\"\n")),
+            pool.get("database.password")
+        );
+        assert_eq!(Some(FlatValue::Integer(443)), pool.get("database.ports.1"));
+        assert_eq!(Some(FlatValue::from("beta")), pool.get("servers.1.host"));
+        assert_eq!(
+            Some(FlatValue::Boolean(true)),
+            pool.get("server.tls.enabled")
+        );
+        assert_eq!(
+            Some(FileFormat::Toml),
+            FileFormat::from_path(Path::new("/etc/app/config.toml"))
+        );
+    }
+
+    #[test]
+    fn toml_errors() {
+        for content in [
+            "no_value",
+            "[unclosed",
+            " = 1",
+            "key = \"unterminated",
+            "key = bare",
+            "key = 1 trailing",
+            "key = 1\nkey = 2",
+            "\"log level\" = \"info\"",
+            "servers = { \"\" = 1 }",
+        ] {
+            let error = FileFormat::Toml.parse("test.toml", content).unwrap_err();
+
+            assert!(
+                matches!(error, ConfigError::Source { ref origin, .. } if origin == "test.toml"),
+                "{content}: {error:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,366 @@
+use std::{fmt::Display, ops::Deref, str::FromStr};
+
+use crate::ConfigError;
+
+/// A segment of a [KeyPath].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum KeySegment {
+    Name(String),
+    Index(usize),
+}
+
+impl Display for KeySegment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Name(name) => write!(f, "{name}"),
+            Self::Index(index) => write!(f, "{index}"),
+        }
+    }
+}
+
+/// ## KeyPath
+///
+/// A validated setting name. Keys are made of segments separated by dots, a segment holds ASCII
+/// letters, digits, `_` and `-`. A segment made of digits is an array index, indices may also be
+/// written between brackets: `servers[0].port` is the same key as `servers.0.port`, which is the
+/// canonical form used by the pools. Indices are written without leading zero, so each key has a
+/// single canonical form.
+///
+/// A key path dereferences to its canonical form, so it can be given to every API taking a
+/// `&str` setting name. The [key!](crate::key) macro checks key literals at compile time.
+///
+/// ```rust
+/// use flat_config::{key, KeyPath, KeySegment, pool::{FlatPool, SimpleFlatPool}};
+///
+/// let key = KeyPath::parse("servers[0].port").unwrap();
+/// let mut pool = SimpleFlatPool::default();
+/// pool.add(&key, 8080.into());
+///
+/// assert_eq!("servers.0.port", key.as_str());
+/// assert_eq!(&KeySegment::Index(0), &key.segments()[1]);
+/// assert_eq!(Some(8080.into()), pool.get(&key!("servers.0.port")));
+/// assert!(KeyPath::parse("http..port").is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct KeyPath {
+    key: String,
+    segments: Vec<KeySegment>,
+}
+
+/// Position and description of the first syntax error of the given key.
+const fn syntax_error(key: &str) -> Option<(usize, &'static str)> {
+    let bytes = key.as_bytes();
+    let mut position = 0;
+    let mut segment_start = true;
+    // Start of the current segment and whether it holds digits only, so far.
+    let mut segment = 0;
+    let mut digits = true;
+
+    if bytes.is_empty() {
+        return Some((0, "empty key"));
+    }
+
+    while position < bytes.len() {
+        match bytes[position] {
+            b'.' if segment_start => return Some((position, "empty segment")),
+            b'.' => {
+                if let Some(error) = index_error(bytes, segment, position, digits) {
+                    return Some(error);
+                }
+                segment_start = true;
+                segment = position + 1;
+                digits = true;
+            }
+            b'[' if segment_start => return Some((position, "index without a name")),
+            b'[' => {
+                if let Some(error) = index_error(bytes, segment, position, digits) {
+                    return Some(error);
+                }
+                let start = position + 1;
+                position = start;
+
+                while position < bytes.len() && bytes[position].is_ascii_digit() {
+                    position += 1;
+                }
+                if position == start {
+                    return Some((position, "index must be a number"));
+                }
+                if position == bytes.len() || bytes[position] != b']' {
+                    return Some((position, "unclosed index"));
+                }
+                if let Some(error) = index_error(bytes, start, position, true) {
+                    return Some(error);
+                }
+                if position + 1 < bytes.len()
+                    && bytes[position + 1] != b'.'
+                    && bytes[position + 1] != b'['
+                {
+                    return Some((position + 1, "expected '.' or '[' after an index"));
+                }
+                // The index is checked, what follows is a dot or another index.
+                digits = false;
+            }
+            b'0'..=b'9' => segment_start = false,
+            b'a'..=b'z' | b'A'..=b'Z' | b'_' | b'-' => {
+                segment_start = false;
+                digits = false;
+            }
+            _ => return Some((position, "invalid character")),
+        }
+        position += 1;
+    }
+
+    if segment_start {
+        return Some((position, "empty segment"));
+    }
+
+    index_error(bytes, segment, position, digits)
+}
+
+/// Error of the index written in `bytes[start..end]`, when `digits` tells the segment is one:
+/// indices have no leading zero and fit in a `usize`.
+const fn index_error(
+    bytes: &[u8],
+    start: usize,
+    end: usize,
+    digits: bool,
+) -> Option<(usize, &'static str)> {
+    if !digits || start >= end {
+        return None;
+    }
+
+    if bytes[start] == b'0' && end - start > 1 {
+        return Some((start, "index with a leading zero"));
+    }
+
+    let mut index: usize = 0;
+    let mut position = start;
+
+    while position < end {
+        let digit = (bytes[position] - b'0') as usize;
+        index = match index.checked_mul(10) {
+            Some(index) => match index.checked_add(digit) {
+                Some(index) => index,
+                None => return Some((start, "index is too large")),
+            },
+            None => return Some((start, "index is too large")),
+        };
+        position += 1;
+    }
+
+    None
+}
+
+impl KeyPath {
+    /// Parse and validate the given key.
+    pub fn parse(key: &str) -> Result<Self, ConfigError> {
+        if let Some((position, reason)) = syntax_error(key) {
+            return Err(ConfigError::IncorrectValue(format!(
+                "invalid key '{key}': {reason} at position {position}"
+            )));
+        }
+
+        let mut segments = Vec::new();
+
+        for segment in key.split(['.', '[']) {
+            segments.push(match segment.strip_suffix(']') {
+                Some(index) => KeySegment::Index(parse_index(key, index)?),
+                None if segment.bytes().all(|b| b.is_ascii_digit()) => {
+                    KeySegment::Index(parse_index(key, segment)?)
+                }
+                None => KeySegment::Name(segment.to_string()),
+            });
+        }
+
+        Ok(Self::from_segments(segments))
+    }
+
+    /// Is the given key valid? This can be evaluated at compile time.
+    pub const fn is_valid(key: &str) -> bool {
+        syntax_error(key).is_none()
+    }
+
+    fn from_segments(segments: Vec<KeySegment>) -> Self {
+        let key: Vec<String> = segments.iter().map(KeySegment::to_string).collect();
+
+        Self {
+            key: key.join("."),
+            segments,
+        }
+    }
+
+    /// Canonical form of the key.
+    pub fn as_str(&self) -> &str {
+        &self.key
+    }
+
+    pub fn segments(&self) -> &[KeySegment] {
+        &self.segments
+    }
+
+    /// Key without its last segment, `None` for a single segment key.
+    pub fn parent(&self) -> Option<Self> {
+        match self.segments.len() {
+            0 | 1 => None,
+            len => Some(Self::from_segments(self.segments[..len - 1].to_vec())),
+        }
+    }
+
+    /// Key of the given child setting, the name may hold several segments.
+    pub fn join(&self, name: &str) -> Result<Self, ConfigError> {
+        let child = Self::parse(name)?;
+        let mut segments = self.segments.clone();
+        segments.extend(child.segments);
+
+        Ok(Self::from_segments(segments))
+    }
+
+    /// Key of the given element of this array setting.
+    pub fn index(&self, index: usize) -> Self {
+        let mut segments = self.segments.clone();
+        segments.push(KeySegment::Index(index));
+
+        Self::from_segments(segments)
+    }
+}
+
+fn parse_index(key: &str, index: &str) -> Result<usize, ConfigError> {
+    index
+        .parse()
+        .map_err(|e| ConfigError::IncorrectValue(format!("invalid key '{key}': index {index} {e}")))
+}
+
+impl Deref for KeyPath {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.key
+    }
+}
+
+impl AsRef<str> for KeyPath {
+    fn as_ref(&self) -> &str {
+        &self.key
+    }
+}
+
+impl Display for KeyPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.key)
+    }
+}
+
+impl FromStr for KeyPath {
+    type Err = ConfigError;
+
+    fn from_str(key: &str) -> Result<Self, Self::Err> {
+        Self::parse(key)
+    }
+}
+
+impl TryFrom<&str> for KeyPath {
+    type Error = ConfigError;
+
+    fn try_from(key: &str) -> Result<Self, Self::Error> {
+        Self::parse(key)
+    }
+}
+
+/// Build a [KeyPath] from a literal checked at compile time.
+///
+/// ```compile_fail
+/// let key = flat_config::key!("http..port");
+/// ```
+///
+/// ```compile_fail
+/// let key = flat_config::key!("servers.99999999999999999999999");
+/// ```
+#[macro_export]
+macro_rules! key {
+    ($key:literal) => {{
+        const _: () = assert!(
+            $crate::KeyPath::is_valid($key),
+            concat!("invalid key: ", $key)
+        );
+
+        $crate::KeyPath::parse($key).unwrap()
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let key = KeyPath::parse("servers[1][2].http-port").unwrap();
+
+        assert_eq!("servers.1.2.http-port", key.as_str());
+        assert_eq!(
+            vec![
+                KeySegment::Name("servers".to_string()),
+                KeySegment::Index(1),
+                KeySegment::Index(2),
+                KeySegment::Name("http-port".to_string()),
+            ],
+            key.segments()
+        );
+        assert_eq!(key, "servers.1.2.http-port".parse().unwrap());
+        assert_eq!(
+            vec![KeySegment::Name("0a".to_string())],
+            KeyPath::parse("0a").unwrap().segments()
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        for (key, reason) in [
+            ("", "empty key at position 0"),
+            ("http..port", "empty segment at position 5"),
+            ("http.", "empty segment at position 5"),
+            (".http", "empty segment at position 0"),
+            ("http port", "invalid character at position 4"),
+            ("servers[x]", "index must be a number at position 8"),
+            ("servers[1", "unclosed index at position 9"),
+            (
+                "servers[1]x",
+                "expected '.' or '[' after an index at position 10",
+            ),
+            ("[1]", "index without a name at position 0"),
+            ("a]", "invalid character at position 1"),
+            ("servers.007", "index with a leading zero at position 8"),
+            ("servers[007]", "index with a leading zero at position 8"),
+            ("servers[0][01]", "index with a leading zero at position 11"),
+            (
+                "a.99999999999999999999999",
+                "index is too large at position 2",
+            ),
+            (
+                "a[99999999999999999999999]",
+                "index is too large at position 2",
+            ),
+        ] {
+            assert_eq!(
+                format!("CONFIGURATION ERROR: Incorrect value: invalid key '{key}': {reason}."),
+                KeyPath::parse(key).unwrap_err().to_string()
+            );
+            assert!(!KeyPath::is_valid(key));
+        }
+    }
+
+    #[test]
+    fn navigation() {
+        let key = key!("servers");
+
+        assert_eq!(
+            "servers.0.port",
+            key.index(0).join("port").unwrap().as_str()
+        );
+        assert_eq!(None, key.parent());
+        assert_eq!(
+            Some(key!("servers.0")),
+            KeyPath::parse("servers[0].port").unwrap().parent()
+        );
+        assert!(key.join("a..b").is_err());
+    }
+}
@@ -1,8 +1,57 @@
+mod address;
+mod byte_size;
 mod config_builder;
+mod constraint;
+#[cfg(feature = "serde")]
+mod de;
+mod diff;
+mod docs;
+mod drop_in;
+mod duration;
 mod error;
+mod export;
+mod flat_enum;
 mod flat_value;
+mod format;
+mod key_path;
+mod nested;
+mod normalize;
+mod path;
 pub mod pool;
+mod reload;
+mod schema;
+mod secret;
+#[cfg(feature = "serde")]
+mod ser;
+mod setting;
+mod sources;
+mod validator;
+mod watch;
 
+pub use address::*;
+pub use byte_size::*;
 pub use config_builder::*;
+pub use constraint::*;
+#[cfg(feature = "serde")]
+pub use de::*;
+pub use diff::*;
+pub use docs::*;
+pub use drop_in::*;
+pub use duration::*;
 pub use error::*;
+pub use export::*;
+pub use flat_enum::*;
 pub use flat_value::*;
+pub use format::*;
+pub use key_path::*;
+pub use normalize::*;
+pub use path::*;
+pub use reload::*;
+pub use schema::*;
+pub use secret::*;
+#[cfg(feature = "serde")]
+pub use ser::*;
+pub use setting::*;
+pub use sources::*;
+pub use validator::*;
+pub use watch::*;
@@ -0,0 +1,181 @@
+use std::{collections::BTreeMap, fmt::Display};
+
+/// Key holding a value while other keys are nested under it, like `tls` and `tls.cert`: once the
+/// keys are nested, `tls` cannot be both a value and a table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct KeyConflict {
+    pub parent: String,
+    pub child: String,
+}
+
+impl Display for KeyConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "'{}' holds a value and is the parent of '{}'",
+            self.parent, self.child
+        )
+    }
+}
+
+#[derive(Debug)]
+pub(crate) enum Node<T> {
+    Value(T),
+    Table(Table<T>),
+}
+
+/// Values nested according to their dotted keys, `database.port` is the `port` value of the
+/// `database` table.
+#[derive(Debug)]
+pub(crate) struct Table<T>(pub BTreeMap<String, Node<T>>);
+
+impl<T> Default for Table<T> {
+    fn default() -> Self {
+        Self(BTreeMap::new())
+    }
+}
+
+impl<T> Table<T> {
+    /// Nest the value under the given dotted key. A conflict between a value and a table is
+    /// reported whatever the insertion order and the table is left unchanged.
+    pub fn insert(&mut self, key: &str, value: T) -> Result<(), KeyConflict> {
+        self.insert_at(key, 0, value)
+    }
+
+    /// Nest the given values, sorted by key so parents come before their children. A value
+    /// nested under another one is kept under its whole key, the conflicts are returned.
+    pub fn nest<K: AsRef<str>>(values: impl IntoIterator<Item = (K, T)>) -> (Self, Vec<KeyConflict>)
+    where
+        T: Clone,
+    {
+        let mut values: Vec<(K, T)> = values.into_iter().collect();
+        values.sort_by(|(a, _), (b, _)| a.as_ref().cmp(b.as_ref()));
+        let mut table = Self::default();
+        let mut conflicts = Vec::new();
+
+        for (key, value) in values {
+            if let Err(conflict) = table.insert(key.as_ref(), value.clone()) {
+                table.insert_verbatim(key.as_ref(), value);
+                conflicts.push(conflict);
+            }
+        }
+
+        (table, conflicts)
+    }
+
+    /// Insert the value under the given key as is, without nesting it.
+    pub fn insert_verbatim(&mut self, key: &str, value: T) {
+        self.0.insert(key.to_string(), Node::Value(value));
+    }
+
+    /// Insert the part of `key` starting at `start` in this table.
+    fn insert_at(&mut self, key: &str, start: usize, value: T) -> Result<(), KeyConflict> {
+        let name = &key[start..];
+
+        match name.split_once('.') {
+            None => match self.0.get(name) {
+                Some(Node::Table(table)) => Err(KeyConflict {
+                    parent: key.to_string(),
+                    child: format!("{key}.{}", table.first_key()),
+                }),
+                _ => {
+                    self.insert_verbatim(name, value);
+                    Ok(())
+                }
+            },
+            Some((head, _)) => {
+                let node = self
+                    .0
+                    .entry(head.to_string())
+                    .or_insert_with(|| Node::Table(Table::default()));
+
+                match node {
+                    Node::Table(table) => table.insert_at(key, start + head.len() + 1, value),
+                    Node::Value(_) => Err(KeyConflict {
+                        parent: key[..start + head.len()].to_string(),
+                        child: key.to_string(),
+                    }),
+                }
+            }
+        }
+    }
+
+    /// First key of the table, relative to it.
+    fn first_key(&self) -> String {
+        match self.0.iter().next() {
+            Some((name, Node::Table(table))) => format!("{name}.{}", table.first_key()),
+            Some((name, Node::Value(_))) => name.clone(),
+            None => String::new(),
+        }
+    }
+
+    /// Tables whose keys are exactly `0..n` are arrays.
+    pub fn is_array(&self) -> bool {
+        !self.0.is_empty()
+            && self.0.keys().all(|key| {
+                key.parse::<usize>()
+                    .is_ok_and(|index| index < self.0.len() && index.to_string() == *key)
+            })
+    }
+
+    /// Elements of an array table in index order.
+    pub fn elements(&self) -> Vec<&Node<T>> {
+        let mut elements: Vec<(usize, &Node<T>)> = self
+            .0
+            .iter()
+            .filter_map(|(key, node)| key.parse().ok().map(|index| (index, node)))
+            .collect();
+        elements.sort_by_key(|(index, _)| *index);
+
+        elements.into_iter().map(|(_, node)| node).collect()
+    }
+
+    /// Array holding values only.
+    pub fn is_scalar_array(&self) -> bool {
+        self.is_array() && self.0.values().all(|node| matches!(node, Node::Value(_)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conflicts() {
+        let mut table = Table::default();
+        table.insert("tls.cert", 1).unwrap();
+        table.insert("tls.key", 2).unwrap();
+        table.insert("server.tls", 3).unwrap();
+
+        assert_eq!(
+            Err(KeyConflict {
+                parent: "tls".to_string(),
+                child: "tls.cert".to_string()
+            }),
+            table.insert("tls", 4)
+        );
+        assert_eq!(
+            "'server.tls' holds a value and is the parent of 'server.tls.cert'",
+            table.insert("server.tls.cert", 5).unwrap_err().to_string()
+        );
+        assert!(matches!(&table.0["tls"], Node::Table(tls) if tls.0.len() == 2));
+        assert!(matches!(&table.0["server"], Node::Table(server) if server.0.len() == 1));
+    }
+
+    #[test]
+    fn nest() {
+        let (table, conflicts) = Table::nest([("tls.cert", 1), ("tls", 2), ("port", 3)]);
+
+        assert_eq!(
+            vec!["port", "tls", "tls.cert"],
+            table.0.keys().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![KeyConflict {
+                parent: "tls".to_string(),
+                child: "tls.cert".to_string()
+            }],
+            conflicts
+        );
+    }
+}
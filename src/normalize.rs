@@ -0,0 +1,105 @@
+/// ## Key normalizer
+///
+/// How setting names are rewritten so the same setting gets the same name whatever source it
+/// comes from. By default, keys are kept verbatim. The separator mappings are applied first, in
+/// declaration order, then case folding and `-`/`_` equivalence.
+///
+/// ```rust
+/// use flat_config::KeyNormalizer;
+///
+/// let normalizer = KeyNormalizer::standard().map_separator("__", ".");
+///
+/// assert_eq!("database_dir", normalizer.normalize("DATABASE-DIR"));
+/// assert_eq!("server.port", normalizer.normalize("SERVER__PORT"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KeyNormalizer {
+    fold_case: bool,
+    dash_as_underscore: bool,
+    separators: Vec<(String, String)>,
+}
+
+impl KeyNormalizer {
+    /// Case folding and `-`/`_` equivalence, the usual policy to match file keys, environment
+    /// variables and command line flags.
+    pub fn standard() -> Self {
+        Self::default().fold_case().dash_as_underscore()
+    }
+
+    /// Compare keys case insensitively, keys are lowercased.
+    pub fn fold_case(mut self) -> Self {
+        self.fold_case = true;
+
+        self
+    }
+
+    /// Consider `-` and `_` as the same character, dashes are replaced by underscores.
+    pub fn dash_as_underscore(mut self) -> Self {
+        self.dash_as_underscore = true;
+
+        self
+    }
+
+    /// Replace the `from` separator by `to`, like `__` by `.` for environment variables standing
+    /// for nested settings.
+    pub fn map_separator(mut self, from: &str, to: &str) -> Self {
+        self.separators.push((from.to_string(), to.to_string()));
+
+        self
+    }
+
+    /// Normalized form of the given key.
+    pub fn normalize(&self, key: &str) -> String {
+        let mut key = key.to_string();
+
+        for (from, to) in self.separators.iter().filter(|(from, _)| !from.is_empty()) {
+            key = key.replace(from.as_str(), to);
+        }
+        if self.fold_case {
+            key = key.to_lowercase();
+        }
+        if self.dash_as_underscore {
+            key = key.replace('-', "_");
+        }
+
+        key
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verbatim() {
+        let normalizer = KeyNormalizer::default();
+
+        assert_eq!("Database-Dir", normalizer.normalize("Database-Dir"));
+    }
+
+    #[test]
+    fn standard() {
+        let normalizer = KeyNormalizer::standard();
+
+        for key in [
+            "database_dir",
+            "database-dir",
+            "DATABASE_DIR",
+            "Database-Dir",
+        ] {
+            assert_eq!("database_dir", normalizer.normalize(key), "{key}");
+        }
+        assert_eq!("database.dir", normalizer.normalize("database.dir"));
+    }
+
+    #[test]
+    fn separators() {
+        let normalizer = KeyNormalizer::default()
+            .map_separator("__", ".")
+            .map_separator("", "x")
+            .fold_case();
+
+        assert_eq!("server.tls.cert", normalizer.normalize("SERVER__TLS__CERT"));
+        assert_eq!("log_level", normalizer.normalize("LOG_LEVEL"));
+    }
+}
@@ -0,0 +1,246 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{ConfigError, FlatValue, TryUnwrap};
+
+/// ## Path options
+///
+/// How to resolve and check a path value. By default, the path is returned as is.
+///
+/// ```rust
+/// use flat_config::{FlatValue, PathOptions};
+///
+/// let options = PathOptions::default()
+///     .relative_to(env!("CARGO_MANIFEST_DIR"))
+///     .must_be_file();
+/// let path = FlatValue::from("Cargo.toml").try_unwrap_path(&options).unwrap();
+///
+/// assert!(path.is_absolute());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PathOptions {
+    expand_home: bool,
+    base_dir: Option<PathBuf>,
+    kind: PathKind,
+    readable: bool,
+    writable: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum PathKind {
+    #[default]
+    Any,
+    Existing,
+    File,
+    Directory,
+}
+
+impl PathOptions {
+    /// Replace a leading `~` by the current user's home directory.
+    pub fn expand_home(mut self) -> Self {
+        self.expand_home = true;
+
+        self
+    }
+
+    /// Resolve relative paths against the given directory, typically the directory of the
+    /// configuration file the value was read from.
+    pub fn relative_to(mut self, base_dir: impl Into<PathBuf>) -> Self {
+        self.base_dir = Some(base_dir.into());
+
+        self
+    }
+
+    /// The path must exist.
+    pub fn must_exist(mut self) -> Self {
+        self.kind = PathKind::Existing;
+
+        self
+    }
+
+    /// The path must be an existing file.
+    pub fn must_be_file(mut self) -> Self {
+        self.kind = PathKind::File;
+
+        self
+    }
+
+    /// The path must be an existing directory.
+    pub fn must_be_dir(mut self) -> Self {
+        self.kind = PathKind::Directory;
+
+        self
+    }
+
+    /// The path must exist and be readable by the current process.
+    pub fn must_be_readable(mut self) -> Self {
+        self.readable = true;
+
+        self
+    }
+
+    /// The path must exist and not be read only. This relies on the permission flags of the
+    /// path, not on the effective rights of the current process.
+    pub fn must_be_writable(mut self) -> Self {
+        self.writable = true;
+
+        self
+    }
+
+    /// Resolve the given raw path and check it according to these options.
+    pub fn resolve(&self, raw: &str) -> Result<PathBuf, ConfigError> {
+        let error =
+            |reason: &str| ConfigError::IncorrectValue(format!("invalid path '{raw}', {reason}"));
+        let mut path = PathBuf::from(raw);
+
+        if self.expand_home {
+            if let Some(rest) = raw.strip_prefix('~') {
+                if rest.is_empty() || rest.starts_with(['/', '\\']) {
+                    let home = home_dir()
+                        .ok_or_else(|| error("the home directory could not be determined"))?;
+                    path = home.join(rest.trim_start_matches(['/', '\\']));
+                }
+            }
+        }
+
+        if let Some(base_dir) = &self.base_dir {
+            if path.is_relative() {
+                path = base_dir.join(path);
+            }
+        }
+
+        self.check(&path).map_err(error)?;
+
+        Ok(path)
+    }
+
+    fn check(&self, path: &Path) -> Result<(), &'static str> {
+        let must_exist = self.kind != PathKind::Any || self.readable || self.writable;
+
+        if !must_exist {
+            return Ok(());
+        }
+
+        let metadata = fs::metadata(path).map_err(|_| "it does not exist")?;
+
+        match self.kind {
+            PathKind::File if !metadata.is_file() => return Err("it is not a file"),
+            PathKind::Directory if !metadata.is_dir() => return Err("it is not a directory"),
+            _ => {}
+        }
+
+        if self.readable {
+            let readable = if metadata.is_dir() {
+                fs::read_dir(path).is_ok()
+            } else {
+                fs::File::open(path).is_ok()
+            };
+
+            if !readable {
+                return Err("it is not readable");
+            }
+        }
+
+        if self.writable && metadata.permissions().readonly() {
+            return Err("it is not writable");
+        }
+
+        Ok(())
+    }
+}
+
+pub(crate) fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .filter(|home| !home.is_empty())
+        .map(PathBuf::from)
+}
+
+impl FlatValue {
+    /// Convert a text value as a path, resolved and checked using the given options.
+    pub fn try_unwrap_path(&self, options: &PathOptions) -> Result<PathBuf, ConfigError> {
+        match self {
+            Self::Text(t) => options.resolve(t),
+            _ => Err(ConfigError::TypeMismatch {
+                expected: "path".to_string(),
+                present: self.display(),
+            }),
+        }
+    }
+}
+
+/// The path is returned as is, use [FlatValue::try_unwrap_path] to resolve or check it.
+impl TryUnwrap<PathBuf> for FlatValue {
+    fn try_unwrap(&self) -> Result<PathBuf, ConfigError> {
+        self.try_unwrap_path(&PathOptions::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest_dir() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+    }
+
+    #[test]
+    fn as_is() {
+        let path: PathBuf = FlatValue::from("some/where").try_unwrap().unwrap();
+        assert_eq!(PathBuf::from("some/where"), path);
+
+        let result: Result<PathBuf, ConfigError> = FlatValue::Integer(1).try_unwrap();
+        assert!(matches!(result, Err(ConfigError::TypeMismatch { .. })));
+    }
+
+    #[test]
+    fn relative_to() {
+        let options = PathOptions::default().relative_to("/etc/app");
+
+        assert_eq!(
+            PathBuf::from("/etc/app/certs/key.pem"),
+            options.resolve("certs/key.pem").unwrap()
+        );
+        assert_eq!(
+            PathBuf::from("/var/lib/app"),
+            options.resolve("/var/lib/app").unwrap()
+        );
+    }
+
+    #[test]
+    fn expand_home() {
+        let Some(home) = home_dir() else {
+            return;
+        };
+        let options = PathOptions::default().expand_home();
+
+        assert_eq!(home.join(".app"), options.resolve("~/.app").unwrap());
+        assert_eq!(home, options.resolve("~").unwrap());
+        assert_eq!(PathBuf::from("~user"), options.resolve("~user").unwrap());
+        assert_eq!(
+            PathBuf::from("~/.app"),
+            PathOptions::default().resolve("~/.app").unwrap()
+        );
+    }
+
+    #[test]
+    fn checks() {
+        let options = PathOptions::default().relative_to(manifest_dir());
+
+        assert!(options.clone().must_exist().resolve("src").is_ok());
+        assert!(options.clone().must_be_dir().resolve("src").is_ok());
+        assert!(options.clone().must_be_file().resolve("src").is_err());
+        assert!(options.clone().must_be_file().resolve("Cargo.toml").is_ok());
+        assert!(options
+            .clone()
+            .must_be_readable()
+            .resolve("Cargo.toml")
+            .is_ok());
+        assert!(matches!(
+            options.must_exist().resolve("unexistent"),
+            Err(ConfigError::IncorrectValue(_))
+        ));
+    }
+}
@@ -0,0 +1,404 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{Debug, Display},
+    path::PathBuf,
+    sync::{Mutex, PoisonError},
+};
+
+use crate::{ConfigError, FlatValue};
+
+use super::{flat_pool::RedactedSettings, FlatPool};
+
+/// ## Deprecation
+///
+/// A deprecated name found in the settings: either an alias of a renamed setting, with its
+/// replacement, or a deprecated setting, with the deprecation message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Deprecation {
+    pub name: String,
+    pub replacement: Option<String>,
+    pub message: Option<String>,
+}
+
+impl Display for Deprecation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "'{}' is deprecated", self.name)?;
+
+        if let Some(replacement) = &self.replacement {
+            write!(f, ", use '{replacement}' instead")?;
+        }
+        if let Some(message) = &self.message {
+            write!(f, ": {message}")?;
+        }
+
+        Ok(())
+    }
+}
+
+type Callback = Box<dyn Fn(&Deprecation) + Send + Sync>;
+
+/// ## AliasedFlatPool
+///
+/// This flat pool lets renamed settings be read under their former names. Reading a setting also
+/// looks for its aliases when it is absent, the current name wins when both are set and aliases
+/// are searched in the order they were declared. Keys are reported under their current names.
+///
+/// Deprecated names (aliases and settings marked deprecated) are listed by
+/// [deprecations](AliasedFlatPool::deprecations), the callback given to
+/// [on_deprecated](AliasedFlatPool::on_deprecated) is called the first time each one is read.
+/// With [deny_deprecated](AliasedFlatPool::deny_deprecated), reading a deprecated name is an
+/// error and the settings only set under a deprecated name are neither listed nor present.
+///
+/// ```rust
+/// use flat_config::pool::{AliasedFlatPool, FlatPool, SimpleFlatPool};
+///
+/// let mut pool = SimpleFlatPool::default();
+/// pool.add("db_dir", "/var/lib/app".into());
+/// let mut pool = AliasedFlatPool::new(pool);
+/// pool.alias("db_dir", "database_dir");
+///
+/// assert_eq!(Some("/var/lib/app".into()), pool.get("database_dir"));
+/// assert_eq!(vec!["database_dir"], pool.keys());
+/// assert_eq!(
+///     "'db_dir' is deprecated, use 'database_dir' instead",
+///     pool.deprecations()[0].to_string()
+/// );
+/// ```
+pub struct AliasedFlatPool<P: FlatPool> {
+    pool: P,
+    aliases: BTreeMap<String, Vec<String>>,
+    deprecated: BTreeMap<String, String>,
+    deny: bool,
+    callback: Option<Callback>,
+    reported: Mutex<BTreeSet<String>>,
+}
+
+impl<P: FlatPool> AliasedFlatPool<P> {
+    pub fn new(pool: P) -> Self {
+        Self {
+            pool,
+            aliases: BTreeMap::new(),
+            deprecated: BTreeMap::new(),
+            deny: false,
+            callback: None,
+            reported: Mutex::new(BTreeSet::new()),
+        }
+    }
+
+    /// Declare `alias` as the former name of the `key` setting.
+    pub fn alias(&mut self, alias: &str, key: &str) -> &mut Self {
+        self.aliases
+            .entry(key.to_string())
+            .or_default()
+            .push(alias.to_string());
+
+        self
+    }
+
+    /// Mark the `key` setting as deprecated, the message tells what to do instead.
+    pub fn deprecate(&mut self, key: &str, message: &str) -> &mut Self {
+        self.deprecated.insert(key.to_string(), message.to_string());
+
+        self
+    }
+
+    /// Make reading a deprecated name an error.
+    pub fn deny_deprecated(&mut self) -> &mut Self {
+        self.deny = true;
+
+        self
+    }
+
+    /// Call the given function the first time each deprecated name is read.
+    pub fn on_deprecated<F>(&mut self, callback: F) -> &mut Self
+    where
+        F: Fn(&Deprecation) + Send + Sync + 'static,
+    {
+        self.callback = Some(Box::new(callback));
+
+        self
+    }
+
+    /// Deprecated names present in the pool, sorted.
+    pub fn deprecations(&self) -> Vec<Deprecation> {
+        let mut deprecations: Vec<Deprecation> = self
+            .aliases
+            .iter()
+            .flat_map(|(key, aliases)| {
+                aliases
+                    .iter()
+                    .filter(|alias| self.pool.has(alias))
+                    .map(|alias| self.alias_deprecation(alias, key))
+            })
+            .chain(
+                self.deprecated
+                    .keys()
+                    .filter(|key| self.pool.has(key))
+                    .filter_map(|key| self.key_deprecation(key)),
+            )
+            .collect();
+        deprecations.sort_by(|a, b| a.name.cmp(&b.name));
+
+        deprecations
+    }
+
+    fn alias_deprecation(&self, alias: &str, key: &str) -> Deprecation {
+        Deprecation {
+            name: alias.to_string(),
+            replacement: Some(key.to_string()),
+            message: self.deprecated.get(alias).cloned(),
+        }
+    }
+
+    fn key_deprecation(&self, key: &str) -> Option<Deprecation> {
+        self.deprecated.get(key).map(|message| Deprecation {
+            name: key.to_string(),
+            replacement: None,
+            message: Some(message.clone()),
+        })
+    }
+
+    /// Name of the field of the inner pool holding the value of the given setting, with the
+    /// deprecation reading it triggers.
+    fn resolve(&self, name: &str) -> Option<(String, Option<Deprecation>)> {
+        if self.pool.has(name) {
+            return Some((name.to_string(), self.key_deprecation(name)));
+        }
+
+        self.aliases
+            .get(name)?
+            .iter()
+            .find(|alias| self.pool.has(alias))
+            .map(|alias| (alias.clone(), Some(self.alias_deprecation(alias, name))))
+    }
+
+    /// Field holding the given setting, reporting or refusing deprecated names.
+    fn lookup(&self, name: &str) -> Result<Option<String>, ConfigError> {
+        let Some((field, deprecation)) = self.resolve(name) else {
+            return Ok(None);
+        };
+
+        if let Some(deprecation) = deprecation {
+            if self.deny {
+                return Err(ConfigError::IncorrectValue(deprecation.to_string()).with_key(name));
+            }
+
+            let first_use = self
+                .reported
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .insert(deprecation.name.clone());
+
+            if let (true, Some(callback)) = (first_use, &self.callback) {
+                callback(&deprecation);
+            }
+        }
+
+        Ok(Some(field))
+    }
+
+    /// Current name of the given field of the inner pool.
+    fn current_name<'a>(&'a self, field: &'a str) -> &'a str {
+        self.aliases
+            .iter()
+            .find(|(_, aliases)| aliases.iter().any(|alias| alias == field))
+            .map_or(field, |(key, _)| key.as_str())
+    }
+}
+
+impl<P: FlatPool> Debug for AliasedFlatPool<P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // The inner settings are shown as is, reading them here must not report deprecations.
+        f.debug_struct("AliasedFlatPool")
+            .field("settings", &RedactedSettings(&self.pool))
+            .field("aliases", &self.aliases)
+            .finish()
+    }
+}
+
+impl<P: FlatPool> FlatPool for AliasedFlatPool<P> {
+    fn get(&self, name: &str) -> Option<FlatValue> {
+        self.get_borrow(name).cloned()
+    }
+
+    fn get_borrow(&self, name: &str) -> Option<&FlatValue> {
+        let field = self.lookup(name).ok()??;
+
+        self.pool.get_borrow(&field)
+    }
+
+    fn require(&self, name: &str) -> Result<FlatValue, ConfigError> {
+        match self.lookup(name)? {
+            Some(field) => self.pool.require(&field),
+            None => Err(ConfigError::Missing {
+                field_name: name.to_string(),
+                fields: format!("{self:?}"),
+            }),
+        }
+    }
+
+    fn unwrap(&self, name: &str) -> FlatValue {
+        self.require(name)
+            .map_err(|e| panic!("This should never happen: {e}"))
+            .unwrap()
+    }
+
+    fn get_or(&self, name: &str, default: FlatValue) -> FlatValue {
+        self.get(name).unwrap_or(default)
+    }
+
+    fn has(&self, name: &str) -> bool {
+        match self.resolve(name) {
+            Some((_, deprecation)) => !self.deny || deprecation.is_none(),
+            None => false,
+        }
+    }
+
+    fn keys(&self) -> Vec<String> {
+        self.pool
+            .keys()
+            .iter()
+            .map(|field| self.current_name(field).to_string())
+            .filter(|key| self.has(key))
+            .collect::<BTreeSet<String>>()
+            .into_iter()
+            .collect()
+    }
+
+    fn is_secret(&self, name: &str) -> bool {
+        self.pool.is_secret(name)
+            || self
+                .resolve(name)
+                .is_some_and(|(field, _)| self.pool.is_secret(&field))
+    }
+
+    fn source_files(&self) -> Vec<PathBuf> {
+        self.pool.source_files()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::pool::SimpleFlatPool;
+
+    fn init_pool() -> AliasedFlatPool<SimpleFlatPool> {
+        let mut pool = SimpleFlatPool::default();
+        pool.add("db_dir", "/var/lib/app".into())
+            .add("port", 80.into())
+            .add("listen_port", 8080.into())
+            .add("dry_run", true.into())
+            .add_secret("pass", "hunter2".into());
+        let mut pool = AliasedFlatPool::new(pool);
+        pool.alias("db_dir", "database_dir")
+            .alias("data_dir", "database_dir")
+            .alias("port", "server.port")
+            .alias("listen_port", "server.port")
+            .alias("pass", "password")
+            .deprecate("dry_run", "use 'mode = \"dry\"'");
+
+        pool
+    }
+
+    #[test]
+    fn aliases() {
+        let pool = init_pool();
+
+        assert_eq!(Some("/var/lib/app".into()), pool.get("database_dir"));
+        assert_eq!(Some(FlatValue::Integer(80)), pool.get("server.port"));
+        assert_eq!(FlatValue::Integer(80), pool.unwrap("server.port"));
+        assert!(pool.has("database_dir"));
+        assert!(pool.is_secret("password"));
+        assert_eq!(
+            vec!["database_dir", "dry_run", "password", "server.port"],
+            pool.keys()
+        );
+        assert!(!format!("{pool:?}").contains("hunter2"));
+    }
+
+    #[test]
+    fn current_name_wins() {
+        let mut pool = SimpleFlatPool::default();
+        pool.add("db_dir", "/old".into())
+            .add("database_dir", "/new".into());
+        let mut pool = AliasedFlatPool::new(pool);
+        pool.alias("db_dir", "database_dir");
+
+        assert_eq!(Some("/new".into()), pool.get("database_dir"));
+    }
+
+    #[test]
+    fn deprecations() {
+        let pool = init_pool();
+        let names: Vec<String> = pool
+            .deprecations()
+            .iter()
+            .map(|deprecation| deprecation.to_string())
+            .collect();
+
+        assert_eq!(
+            vec![
+                "'db_dir' is deprecated, use 'database_dir' instead",
+                "'dry_run' is deprecated: use 'mode = \"dry\"'",
+                "'listen_port' is deprecated, use 'server.port' instead",
+                "'pass' is deprecated, use 'password' instead",
+                "'port' is deprecated, use 'server.port' instead",
+            ],
+            names
+        );
+    }
+
+    #[test]
+    fn callback() {
+        let used = Arc::new(Mutex::new(Vec::new()));
+        let mut pool = init_pool();
+        let recorder = used.clone();
+        pool.on_deprecated(move |deprecation| {
+            recorder.lock().unwrap().push(deprecation.name.clone())
+        });
+
+        pool.get("database_dir");
+        pool.get("database_dir");
+        pool.get("dry_run");
+        pool.get("unexistent");
+
+        assert_eq!(vec!["db_dir", "dry_run"], *used.lock().unwrap());
+    }
+
+    #[test]
+    fn deny_deprecated() {
+        let mut pool = init_pool();
+        pool.deny_deprecated();
+
+        assert_eq!(None, pool.get("database_dir"));
+        assert!(matches!(
+            pool.require("database_dir"),
+            Err(ConfigError::Setting { ref key, .. }) if key == "database_dir"
+        ));
+        assert!(pool.require("dry_run").is_err());
+        assert!(!pool.has("database_dir"));
+        assert!(!pool.has("dry_run"));
+        assert!(pool.keys().is_empty());
+
+        let mut pool = SimpleFlatPool::default();
+        pool.add("port", 80.into())
+            .add("server.port", 8080.into())
+            .add("db_dir", "/var/lib/app".into());
+        let mut pool = AliasedFlatPool::new(pool);
+        pool.alias("port", "server.port")
+            .alias("db_dir", "database_dir")
+            .deny_deprecated();
+
+        assert!(pool.has("server.port"));
+        assert_eq!(vec!["server.port"], pool.keys());
+        for key in pool.keys() {
+            assert!(pool.get(&key).is_some(), "{key}");
+        }
+        assert!(matches!(
+            pool.require("unexistent"),
+            Err(ConfigError::Missing { .. })
+        ));
+    }
+}
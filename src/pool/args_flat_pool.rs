@@ -0,0 +1,179 @@
+use std::fmt::Debug;
+
+use crate::{format::parse_scalar, ConfigError, FlatValue, KeyNormalizer};
+
+use super::{flat_pool::RedactedSettings, FlatPool, SimpleFlatPool};
+
+/// ## ArgsFlatPool
+///
+/// This flat pool holds the settings given as command line flags. A setting is read from the flag
+/// named after it by [cli_flag_name](crate::cli_flag_name): `server.port` is read from
+/// `--server.port=8080` or `--server.port 8080`. A flag followed by another flag or by nothing,
+/// like `--dry-run`, is `true`; values starting with `-` must be given after `=`. Values are
+/// parsed like INI values. Other arguments are ignored, so positional arguments go before the
+/// flags or after `--`, which ends the flags.
+///
+/// Flags are stored under their setting key, lower cased with dashes replaced by underscores, so
+/// they are listed and read like the settings of any other source. As a consequence a setting
+/// whose key holds a `-`, like `http-port`, cannot be set from the command line: `--http-port`
+/// sets `http_port`. Applications parsing their
+/// arguments with a dedicated library can give their own pool to the
+/// [ConfigSources](crate::ConfigSources) preset instead.
+///
+/// ```rust
+/// use flat_config::pool::{ArgsFlatPool, FlatPool};
+///
+/// let pool = ArgsFlatPool::parse(["input.txt", "--server.port=8080", "--dry-run"]);
+///
+/// assert_eq!(Some(8080.into()), pool.get("server.port"));
+/// assert_eq!(vec!["dry_run", "server.port"], pool.keys());
+/// ```
+pub struct ArgsFlatPool {
+    pool: SimpleFlatPool,
+}
+
+impl ArgsFlatPool {
+    /// Parse the arguments of the current process, the program name excluded.
+    pub fn from_env() -> Self {
+        Self::parse(std::env::args().skip(1))
+    }
+
+    /// Parse the given arguments.
+    pub fn parse(args: impl IntoIterator<Item = impl AsRef<str>>) -> Self {
+        let normalizer = KeyNormalizer::default().fold_case().dash_as_underscore();
+        let mut pool = SimpleFlatPool::default();
+        let mut args = args.into_iter().peekable();
+
+        while let Some(arg) = args.next() {
+            let arg = arg.as_ref();
+
+            if arg == "--" {
+                break;
+            }
+
+            let Some(flag) = arg.strip_prefix("--").filter(|flag| !flag.is_empty()) else {
+                continue;
+            };
+
+            match flag.split_once('=') {
+                Some((name, value)) => pool.add(&normalizer.normalize(name), parse_scalar(value)),
+                None => match args.next_if(|value| !value.as_ref().starts_with('-')) {
+                    Some(value) => {
+                        pool.add(&normalizer.normalize(flag), parse_scalar(value.as_ref()))
+                    }
+                    None => pool.add(&normalizer.normalize(flag), FlatValue::Boolean(true)),
+                },
+            };
+        }
+
+        Self { pool }
+    }
+
+    /// Mark the settings matching the given pattern as secret, the pattern may contain `*`
+    /// wildcards like `*password*`.
+    pub fn mark_secret(&mut self, pattern: &str) -> &mut Self {
+        self.pool.mark_secret(pattern);
+
+        self
+    }
+}
+
+impl Debug for ArgsFlatPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ArgsFlatPool")
+            .field("settings", &RedactedSettings(self))
+            .finish()
+    }
+}
+
+impl FlatPool for ArgsFlatPool {
+    fn get(&self, name: &str) -> Option<FlatValue> {
+        self.get_borrow(name).cloned()
+    }
+
+    fn get_borrow(&self, name: &str) -> Option<&FlatValue> {
+        self.pool.get_borrow(name)
+    }
+
+    fn require(&self, name: &str) -> Result<FlatValue, ConfigError> {
+        self.get(name).ok_or_else(|| ConfigError::Missing {
+            field_name: name.to_string(),
+            fields: format!("{self:?}"),
+        })
+    }
+
+    fn unwrap(&self, name: &str) -> FlatValue {
+        self.require(name)
+            .map_err(|e| panic!("This should never happen: {e}"))
+            .unwrap()
+    }
+
+    fn get_or(&self, name: &str, default: FlatValue) -> FlatValue {
+        self.get(name).unwrap_or(default)
+    }
+
+    fn has(&self, name: &str) -> bool {
+        self.pool.has(name)
+    }
+
+    fn keys(&self) -> Vec<String> {
+        self.pool.keys()
+    }
+
+    fn is_secret(&self, name: &str) -> bool {
+        self.pool.is_secret(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let mut pool = ArgsFlatPool::parse([
+            "serve",
+            "--server.port=8080",
+            "--Log-Level=debug",
+            "--dry-run",
+            "-v",
+            "--password=hunter2",
+            "--",
+            "--ignored",
+        ]);
+        pool.mark_secret("password");
+
+        assert_eq!(Some(8080.into()), pool.get("server.port"));
+        assert_eq!(Some("debug".into()), pool.get("log_level"));
+        assert_eq!(Some(true.into()), pool.get("dry_run"));
+        assert!(!pool.has("ignored"));
+        assert!(!pool.has("server_port"));
+        assert!(!format!("{pool:?}").contains("hunter2"));
+        assert_eq!(
+            vec!["dry_run", "log_level", "password", "server.port"],
+            pool.keys()
+        );
+
+        let pool = ArgsFlatPool::parse(["--server.port", "8080", "--verbose", "--", "input.txt"]);
+        assert_eq!(Some(8080.into()), pool.get("server.port"));
+        assert_eq!(Some(true.into()), pool.get("verbose"));
+
+        let pool = ArgsFlatPool::parse(["--offset", "-5", "--offset-text=-5"]);
+        assert_eq!(Some(true.into()), pool.get("offset"));
+        assert_eq!(Some((-5).into()), pool.get("offset_text"));
+
+        let flag = crate::cli_flag_name("server.dry_run");
+        let pool = ArgsFlatPool::parse([format!("{flag}=false")]);
+        assert_eq!(vec!["server.dry_run"], pool.keys());
+    }
+
+    #[test]
+    fn dashed_keys() {
+        let flag = crate::cli_flag_name("http-port");
+        let pool = ArgsFlatPool::parse([format!("{flag}=8080")]);
+
+        assert_eq!("--http-port", flag);
+        assert!(!pool.has("http-port"));
+        assert_eq!(Some(8080.into()), pool.get("http_port"));
+    }
+}
@@ -0,0 +1,183 @@
+use std::fmt::Debug;
+
+use crate::{format::parse_scalar, ConfigError, FlatValue, KeyNormalizer};
+
+use super::{flat_pool::RedactedSettings, FlatPool, SimpleFlatPool};
+
+/// ## EnvFlatPool
+///
+/// This flat pool holds the environment variables starting with a prefix. A setting is read from
+/// the variable named after it by [env_var_name](crate::env_var_name): `server.port` is read from
+/// `APP_SERVER__PORT` with the `APP_` prefix, `__` separating the key segments. Values are parsed
+/// like INI values: `true`, `false`, integers, double quoted or bare texts.
+///
+/// Variables are stored under their setting key, lower cased, so they are listed and read like
+/// the settings of any other source. Variable names cannot hold a `-`, so a setting whose key holds
+/// one, like `http-port`, cannot be set from the environment: `APP_HTTP_PORT` sets `http_port`.
+///
+/// ```rust
+/// use flat_config::pool::{EnvFlatPool, FlatPool};
+///
+/// let pool = EnvFlatPool::from_vars(
+///     "APP_",
+///     [("APP_SERVER__PORT", "8080"), ("APP_DRY_RUN", "true"), ("HOME", "/root")],
+/// );
+///
+/// assert_eq!(Some(8080.into()), pool.get("server.port"));
+/// assert_eq!(vec!["dry_run", "server.port"], pool.keys());
+/// ```
+pub struct EnvFlatPool {
+    prefix: String,
+    pool: SimpleFlatPool,
+}
+
+impl EnvFlatPool {
+    /// Read the variables of the current process starting with the given prefix, variables that
+    /// are not valid unicode are ignored.
+    pub fn new(prefix: &str) -> Self {
+        let vars = std::env::vars_os().filter_map(|(name, value)| {
+            Some((name.into_string().ok()?, value.into_string().ok()?))
+        });
+
+        Self::from_vars(prefix, vars)
+    }
+
+    /// Keep the given variables starting with the prefix.
+    pub fn from_vars<K, V>(prefix: &str, vars: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let normalizer = KeyNormalizer::default()
+            .map_separator("__", ".")
+            .fold_case();
+        let mut pool = SimpleFlatPool::default();
+
+        for (name, value) in vars {
+            if let Some(key) = name.as_ref().strip_prefix(prefix).filter(|k| !k.is_empty()) {
+                pool.add(&normalizer.normalize(key), parse_scalar(value.as_ref()));
+            }
+        }
+
+        Self {
+            prefix: prefix.to_string(),
+            pool,
+        }
+    }
+
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Mark the settings matching the given pattern as secret, the pattern may contain `*`
+    /// wildcards like `*password*`.
+    pub fn mark_secret(&mut self, pattern: &str) -> &mut Self {
+        self.pool.mark_secret(pattern);
+
+        self
+    }
+}
+
+impl Debug for EnvFlatPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EnvFlatPool")
+            .field("prefix", &self.prefix)
+            .field("settings", &RedactedSettings(self))
+            .finish()
+    }
+}
+
+impl FlatPool for EnvFlatPool {
+    fn get(&self, name: &str) -> Option<FlatValue> {
+        self.get_borrow(name).cloned()
+    }
+
+    fn get_borrow(&self, name: &str) -> Option<&FlatValue> {
+        self.pool.get_borrow(name)
+    }
+
+    fn require(&self, name: &str) -> Result<FlatValue, ConfigError> {
+        self.get(name).ok_or_else(|| ConfigError::Missing {
+            field_name: name.to_string(),
+            fields: format!("{self:?}"),
+        })
+    }
+
+    fn unwrap(&self, name: &str) -> FlatValue {
+        self.require(name)
+            .map_err(|e| panic!("This should never happen: {e}"))
+            .unwrap()
+    }
+
+    fn get_or(&self, name: &str, default: FlatValue) -> FlatValue {
+        self.get(name).unwrap_or(default)
+    }
+
+    fn has(&self, name: &str) -> bool {
+        self.pool.has(name)
+    }
+
+    fn keys(&self) -> Vec<String> {
+        self.pool.keys()
+    }
+
+    fn is_secret(&self, name: &str) -> bool {
+        self.pool.is_secret(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_vars() {
+        let mut pool = EnvFlatPool::from_vars(
+            "APP_",
+            [
+                ("APP_DATABASE__DIR", "/var/lib/app"),
+                ("APP_DRY_RUN", "true"),
+                ("APP_LOG__LEVEL", "\"debug\""),
+                ("app_workers", "4"),
+                ("APP_database__password", "hunter2"),
+                ("APP_", "ignored"),
+                ("PATH", "/bin"),
+            ],
+        );
+        pool.mark_secret("database.password");
+
+        assert_eq!("APP_", pool.prefix());
+        assert_eq!(Some("/var/lib/app".into()), pool.get("database.dir"));
+        assert_eq!(Some(true.into()), pool.get("dry_run"));
+        assert_eq!(Some("debug".into()), pool.get("log.level"));
+        assert!(!pool.has("workers"));
+        assert!(!pool.has("log_level"));
+        assert!(pool.is_secret("database.password"));
+        assert!(!format!("{pool:?}").contains("hunter2"));
+        assert_eq!(
+            vec!["database.dir", "database.password", "dry_run", "log.level"],
+            pool.keys()
+        );
+
+        let name = crate::env_var_name("APP_", "server.dry_run");
+        let pool = EnvFlatPool::from_vars("APP_", [(name, "true")]);
+        assert_eq!(vec!["server.dry_run"], pool.keys());
+    }
+
+    #[test]
+    fn dashed_keys() {
+        let name = crate::env_var_name("APP_", "http-port");
+        let pool = EnvFlatPool::from_vars("APP_", [(name.as_str(), "8080")]);
+
+        assert_eq!("APP_HTTP_PORT", name);
+        assert!(!pool.has("http-port"));
+        assert_eq!(Some(8080.into()), pool.get("http_port"));
+    }
+
+    #[test]
+    fn process_env() {
+        let pool = EnvFlatPool::new("FLAT_CONFIG_UNSET_PREFIX_");
+
+        assert!(pool.keys().is_empty());
+    }
+}
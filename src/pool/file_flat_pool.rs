@@ -0,0 +1,167 @@
+use std::{
+    fmt::Debug,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{ConfigError, FileFormat, FlatValue};
+
+use super::{flat_pool::RedactedSettings, FlatPool, SimpleFlatPool};
+
+/// ## FileFlatPool
+///
+/// This flat pool holds the settings read from a configuration file. The file is read once when
+/// the pool is loaded, a [ReloadableConfig](crate::ReloadableConfig) can be used to pick up
+/// changes.
+pub struct FileFlatPool {
+    path: PathBuf,
+    pool: SimpleFlatPool,
+}
+
+impl FileFlatPool {
+    /// Load the given file, its format is guessed from its extension.
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self, ConfigError> {
+        let path = path.into();
+        let format = FileFormat::from_path(&path).ok_or_else(|| ConfigError::Source {
+            origin: path.display().to_string(),
+            message: "unknown file format".to_string(),
+        })?;
+
+        Self::load_with_format(path, format)
+    }
+
+    /// Load the given file using the given format.
+    pub fn load_with_format(
+        path: impl Into<PathBuf>,
+        format: FileFormat,
+    ) -> Result<Self, ConfigError> {
+        let path = path.into();
+        let origin = path.display().to_string();
+        let content = fs::read_to_string(&path).map_err(|e| ConfigError::Source {
+            origin: origin.clone(),
+            message: e.to_string(),
+        })?;
+        let pool = format.parse(&origin, &content)?;
+
+        Ok(Self { path, pool })
+    }
+
+    /// Path of the file the settings were read from.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Mark the fields matching the given pattern as secret, the pattern may contain `*`
+    /// wildcards like `*password*`.
+    pub fn mark_secret(&mut self, pattern: &str) -> &mut Self {
+        self.pool.mark_secret(pattern);
+
+        self
+    }
+
+    /// Mark all the fields of this file as secret.
+    pub fn mark_all_secret(&mut self) -> &mut Self {
+        self.pool.mark_all_secret();
+
+        self
+    }
+}
+
+impl Debug for FileFlatPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FileFlatPool")
+            .field("path", &self.path)
+            .field("settings", &RedactedSettings(self))
+            .finish()
+    }
+}
+
+impl FlatPool for FileFlatPool {
+    fn get(&self, name: &str) -> Option<FlatValue> {
+        self.pool.get(name)
+    }
+
+    fn get_borrow(&self, name: &str) -> Option<&FlatValue> {
+        self.pool.get_borrow(name)
+    }
+
+    fn require(&self, name: &str) -> Result<FlatValue, ConfigError> {
+        self.get(name).ok_or_else(|| ConfigError::Missing {
+            field_name: name.to_string(),
+            fields: format!("{self:?}"),
+        })
+    }
+
+    fn unwrap(&self, name: &str) -> FlatValue {
+        self.require(name)
+            .map_err(|e| panic!("This should never happen: {e}"))
+            .unwrap()
+    }
+
+    fn get_or(&self, name: &str, default: FlatValue) -> FlatValue {
+        self.pool.get_or(name, default)
+    }
+
+    fn has(&self, name: &str) -> bool {
+        self.pool.has(name)
+    }
+
+    fn keys(&self) -> Vec<String> {
+        self.pool.keys()
+    }
+
+    fn is_secret(&self, name: &str) -> bool {
+        self.pool.is_secret(name)
+    }
+
+    fn source_files(&self) -> Vec<PathBuf> {
+        vec![self.path.clone()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_file(name: &str, content: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("flat_config_file_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        fs::write(&path, content).unwrap();
+
+        path
+    }
+
+    #[test]
+    fn load() {
+        let path = write_file("load.conf", "[database]\nport = 5432\npassword = secret\n");
+        let mut pool = FileFlatPool::load(&path).unwrap();
+        pool.mark_secret("*password*");
+
+        assert_eq!(path.as_path(), pool.path());
+        assert_eq!(Some(FlatValue::Integer(5432)), pool.get("database.port"));
+        assert_eq!(vec![path.clone()], pool.source_files());
+        assert!(!format!("{pool:?}").contains("secret"));
+
+        let error = pool.require("unexistent").unwrap_err();
+        assert!(!format!("{error}").contains("secret"));
+    }
+
+    #[test]
+    fn load_errors() {
+        assert!(matches!(
+            FileFlatPool::load("/unexistent/app.conf"),
+            Err(ConfigError::Source { .. })
+        ));
+        assert!(matches!(
+            FileFlatPool::load("/unexistent/app.unknown"),
+            Err(ConfigError::Source { .. })
+        ));
+
+        let path = write_file("broken.ini", "broken");
+        let error = FileFlatPool::load(&path).unwrap_err();
+        assert!(
+            matches!(error, ConfigError::Source { ref origin, .. } if origin.ends_with("broken.ini"))
+        );
+    }
+}
@@ -1,4 +1,6 @@
-use crate::{ConfigError, FlatValue};
+use std::{fmt::Debug, path::PathBuf};
+
+use crate::{ConfigError, FlatValue, KeyPath, REDACTED};
 
 /// ## FlatPool trait
 ///
@@ -25,4 +27,51 @@ pub trait FlatPool: std::fmt::Debug {
 
     /// Is this field present in the pool?
     fn has(&self, name: &str) -> bool;
+
+    /// Names of all the fields present in the pool, sorted.
+    fn keys(&self) -> Vec<String>;
+
+    /// Is this field secret? Secret values are redacted in debug output, error messages and
+    /// exports.
+    fn is_secret(&self, _name: &str) -> bool {
+        false
+    }
+
+    /// Files the settings of this pool were read from.
+    fn source_files(&self) -> Vec<PathBuf> {
+        Vec::new()
+    }
+
+    /// Check every key of the pool is a valid [KeyPath], malformed keys are reported at once.
+    fn check_keys(&self) -> Result<(), ConfigError> {
+        let errors: Vec<ConfigError> = self
+            .keys()
+            .iter()
+            .filter_map(|key| KeyPath::parse(key).err())
+            .collect();
+
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(ConfigError::Invalid(errors)),
+        }
+    }
+}
+
+/// Debug representation of the settings of a pool with the secret values redacted.
+pub(crate) struct RedactedSettings<'a, P: ?Sized>(pub(crate) &'a P);
+
+impl<P: FlatPool + ?Sized> Debug for RedactedSettings<'_, P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut map = f.debug_map();
+
+        for key in self.0.keys() {
+            if self.0.is_secret(&key) {
+                map.entry(&key, &REDACTED);
+            } else if let Some(value) = self.0.get_borrow(&key) {
+                map.entry(&key, value);
+            }
+        }
+
+        map.finish()
+    }
 }
@@ -0,0 +1,367 @@
+use std::{
+    fmt::Debug,
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+use crate::{secret::wildcard_match, ConfigError, FlatValue};
+
+use super::{flat_pool::RedactedSettings, FileFlatPool, FlatPool};
+
+/// Key of the include directive.
+const INCLUDE: &str = "include";
+
+/// Default limit of nested includes.
+pub const MAX_INCLUDE_DEPTH: usize = 8;
+
+/// Is the given key part of the include directive (`include` or `include.<index>`)?
+fn is_directive(key: &str) -> bool {
+    key == INCLUDE
+        || key
+            .strip_prefix("include.")
+            .is_some_and(|index| index.bytes().all(|b| b.is_ascii_digit()))
+}
+
+fn source_error(path: &Path, message: String) -> ConfigError {
+    ConfigError::Source {
+        origin: path.display().to_string(),
+        message,
+    }
+}
+
+/// Paths matching the given pattern, sorted. `*` matches any part of a file or directory name.
+fn expand(pattern: &Path) -> Result<Vec<PathBuf>, ConfigError> {
+    if !pattern.to_string_lossy().contains('*') {
+        return Ok(vec![pattern.to_path_buf()]);
+    }
+
+    let mut paths = vec![PathBuf::new()];
+
+    for component in pattern.components() {
+        let name = component.as_os_str().to_string_lossy();
+
+        if !matches!(component, Component::Normal(_)) || !name.contains('*') {
+            paths.iter_mut().for_each(|path| path.push(name.as_ref()));
+            continue;
+        }
+
+        let mut matches = Vec::new();
+
+        for dir in paths.iter().filter(|path| path.is_dir()) {
+            let entries = fs::read_dir(dir).map_err(|e| source_error(dir, e.to_string()))?;
+
+            for entry in entries {
+                let entry = entry.map_err(|e| source_error(dir, e.to_string()))?;
+
+                if wildcard_match(&name, &entry.file_name().to_string_lossy()) {
+                    matches.push(entry.path());
+                }
+            }
+        }
+        paths = matches;
+    }
+
+    paths.retain(|path| path.is_file());
+    paths.sort();
+
+    Ok(paths)
+}
+
+/// ## IncludeFlatPool
+///
+/// This flat pool reads a configuration file and the files it includes. The `include` setting
+/// lists the included files, in an array or as a single text, relative to the including file.
+/// Names may contain `*` wildcards, the matching files are included in alphabetical order and a
+/// pattern matching nothing is not an error.
+///
+/// Each file is a layer: included files come first, in the order they are listed, and the
+/// including file overrides them. Included files may include other files, up to a depth limit,
+/// and including a file that is already being read is an error.
+///
+/// `include` is a reserved key: `include` and its elements `include.0`, `include.1`… are read as
+/// the directive in every file and never show as settings, whatever the file format. Other keys
+/// under it, like `include.paths`, are plain settings.
+///
+/// ```rust,no_run
+/// use flat_config::pool::{FlatPool, IncludeFlatPool};
+///
+/// // service.toml:
+/// //   include = ["common/*.toml"]
+/// //   name = "billing"
+/// let pool = IncludeFlatPool::load("/etc/billing/service.toml").unwrap();
+///
+/// println!("log.level comes from {:?}", pool.source_of("log.level"));
+/// ```
+pub struct IncludeFlatPool {
+    layers: Vec<FileFlatPool>,
+}
+
+impl IncludeFlatPool {
+    /// Load the given file and its includes, nested at most [MAX_INCLUDE_DEPTH] levels deep.
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self, ConfigError> {
+        Self::load_with_max_depth(path, MAX_INCLUDE_DEPTH)
+    }
+
+    /// Load the given file and its includes, nested at most `max_depth` levels deep.
+    pub fn load_with_max_depth(
+        path: impl Into<PathBuf>,
+        max_depth: usize,
+    ) -> Result<Self, ConfigError> {
+        let mut layers = Vec::new();
+        load_layers(&path.into(), max_depth, &mut Vec::new(), &mut layers)?;
+
+        Ok(Self { layers })
+    }
+
+    /// One layer per file, from the lowest to the highest precedence.
+    pub fn layers(&self) -> &[FileFlatPool] {
+        &self.layers
+    }
+
+    /// File the value of the given setting is read from.
+    pub fn source_of(&self, name: &str) -> Option<&Path> {
+        self.layer_of(name).map(FileFlatPool::path)
+    }
+
+    fn layer_of(&self, name: &str) -> Option<&FileFlatPool> {
+        match is_directive(name) {
+            true => None,
+            false => self.layers.iter().rev().find(|layer| layer.has(name)),
+        }
+    }
+
+    /// Mark the fields matching the given pattern as secret in every file, the pattern may
+    /// contain `*` wildcards like `*password*`.
+    pub fn mark_secret(&mut self, pattern: &str) -> &mut Self {
+        for layer in &mut self.layers {
+            layer.mark_secret(pattern);
+        }
+
+        self
+    }
+}
+
+/// Load the includes of the given file then the file itself, `stack` holds the files being read.
+fn load_layers(
+    path: &Path,
+    max_depth: usize,
+    stack: &mut Vec<PathBuf>,
+    layers: &mut Vec<FileFlatPool>,
+) -> Result<(), ConfigError> {
+    let canonical = path
+        .canonicalize()
+        .map_err(|e| source_error(path, e.to_string()))?;
+
+    if stack.contains(&canonical) {
+        let cycle: Vec<String> = stack
+            .iter()
+            .skip_while(|file| **file != canonical)
+            .chain([&canonical])
+            .map(|file| file.display().to_string())
+            .collect();
+
+        return Err(source_error(
+            path,
+            format!("include cycle {}", cycle.join(" -> ")),
+        ));
+    }
+    if stack.len() > max_depth {
+        return Err(source_error(
+            path,
+            format!("includes are nested more than {max_depth} levels deep"),
+        ));
+    }
+
+    let file = FileFlatPool::load(path)?;
+    let base_dir = path.parent().unwrap_or(Path::new(""));
+    let patterns = match file.get(INCLUDE) {
+        Some(FlatValue::Text(pattern)) => vec![pattern],
+        Some(_) => return Err(source_error(path, "include must be a text".to_string())),
+        None => (0..)
+            .map_while(|index| file.get(&format!("{INCLUDE}.{index}")))
+            .map(|value| match value {
+                FlatValue::Text(pattern) => Ok(pattern),
+                _ => Err(source_error(path, "include must list texts".to_string())),
+            })
+            .collect::<Result<_, _>>()?,
+    };
+
+    stack.push(canonical);
+    for pattern in patterns {
+        for included in expand(&base_dir.join(pattern))? {
+            load_layers(&included, max_depth, stack, layers)?;
+        }
+    }
+    stack.pop();
+    layers.push(file);
+
+    Ok(())
+}
+
+impl Debug for IncludeFlatPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IncludeFlatPool")
+            .field("files", &self.source_files())
+            .field("settings", &RedactedSettings(self))
+            .finish()
+    }
+}
+
+impl FlatPool for IncludeFlatPool {
+    fn get(&self, name: &str) -> Option<FlatValue> {
+        self.get_borrow(name).cloned()
+    }
+
+    fn get_borrow(&self, name: &str) -> Option<&FlatValue> {
+        self.layer_of(name)?.get_borrow(name)
+    }
+
+    fn require(&self, name: &str) -> Result<FlatValue, ConfigError> {
+        self.get(name).ok_or_else(|| ConfigError::Missing {
+            field_name: name.to_string(),
+            fields: format!("{self:?}"),
+        })
+    }
+
+    fn unwrap(&self, name: &str) -> FlatValue {
+        self.require(name)
+            .map_err(|e| panic!("This should never happen: {e}"))
+            .unwrap()
+    }
+
+    fn get_or(&self, name: &str, default: FlatValue) -> FlatValue {
+        self.get(name).unwrap_or(default)
+    }
+
+    fn has(&self, name: &str) -> bool {
+        self.layer_of(name).is_some()
+    }
+
+    fn keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self
+            .layers
+            .iter()
+            .flat_map(|layer| layer.keys())
+            .filter(|key| !is_directive(key))
+            .collect();
+        keys.sort();
+        keys.dedup();
+
+        keys
+    }
+
+    fn is_secret(&self, name: &str) -> bool {
+        self.layer_of(name)
+            .is_some_and(|layer| layer.is_secret(name))
+    }
+
+    fn source_files(&self) -> Vec<PathBuf> {
+        self.layers
+            .iter()
+            .map(|layer| layer.path().to_path_buf())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_file(dir: &str, name: &str, content: &str) -> PathBuf {
+        let dir = std::env::temp_dir()
+            .join(format!("flat_config_include_{}", std::process::id()))
+            .join(dir);
+        let path = dir.join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, content).unwrap();
+
+        path
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn includes() {
+        write_file(
+            "service",
+            "common/b_metrics.toml",
+            "metrics.port = 9100\nlog.level = \"debug\"\n",
+        );
+        write_file(
+            "service",
+            "common/a_logging.toml",
+            "log.level = \"info\"\nlog.format = \"json\"\n",
+        );
+        write_file("service", "common/notes.txt", "ignored");
+        let secrets = write_file("service", "secrets.ini", "password = hunter2\n");
+        let path = write_file(
+            "service",
+            "service.toml",
+            "include = [\"common/*.toml\", \"secrets.ini\", \"missing/*.toml\"]\nlog.format = \"text\"\n",
+        );
+        let mut pool = IncludeFlatPool::load(&path).unwrap();
+        pool.mark_secret("password");
+
+        assert_eq!(4, pool.layers().len());
+        assert_eq!(Some("debug".into()), pool.get("log.level"));
+        assert_eq!(Some("text".into()), pool.get("log.format"));
+        assert!(pool
+            .source_of("log.level")
+            .unwrap()
+            .ends_with("common/b_metrics.toml"));
+        assert_eq!(Some(path.as_path()), pool.source_of("log.format"));
+        assert_eq!(Some(secrets.as_path()), pool.source_of("password"));
+        assert!(!pool.has("include.0"));
+        assert!(!format!("{pool:?}").contains("hunter2"));
+        assert_eq!(
+            vec!["log.format", "log.level", "metrics.port", "password"],
+            pool.keys()
+        );
+        assert_eq!(path, *pool.source_files().last().unwrap());
+    }
+
+    #[test]
+    fn nested() {
+        write_file("nested", "c.ini", "c = 3\n");
+        write_file("nested", "sub/b.ini", "include = ../c.ini\nb = 2\n");
+        let path = write_file("nested", "a.ini", "include = sub/b.ini\na = 1\n");
+
+        let pool = IncludeFlatPool::load(&path).unwrap();
+        assert_eq!(vec!["a", "b", "c"], pool.keys());
+
+        let error = IncludeFlatPool::load_with_max_depth(&path, 1).unwrap_err();
+        assert!(error.to_string().contains("nested more than 1 levels deep"));
+    }
+
+    #[test]
+    fn reserved_key() {
+        let path = write_file(
+            "reserved",
+            "a.ini",
+            "[include]\npaths = /usr/include\n0 = b.ini\n",
+        );
+        write_file("reserved", "b.ini", "b = 2\n");
+
+        let pool = IncludeFlatPool::load(&path).unwrap();
+
+        assert_eq!(vec!["b", "include.paths"], pool.keys());
+        assert_eq!(Some("/usr/include".into()), pool.get("include.paths"));
+        assert!(!pool.has("include.0"));
+    }
+
+    #[test]
+    fn errors() {
+        write_file("cycle", "b.ini", "include = a.ini\n");
+        let path = write_file("cycle", "a.ini", "include = b.ini\n");
+        let error = IncludeFlatPool::load(&path).unwrap_err();
+        assert!(
+            matches!(error, ConfigError::Source { ref message, .. } if message.starts_with("include cycle") && message.ends_with("a.ini")),
+            "{error}"
+        );
+
+        let path = write_file("missing", "a.ini", "include = unexistent.ini\n");
+        assert!(IncludeFlatPool::load(&path).is_err());
+
+        let path = write_file("type", "a.ini", "include = 1\n");
+        assert!(IncludeFlatPool::load(&path).is_err());
+    }
+}
@@ -0,0 +1,400 @@
+use std::{collections::HashMap, fmt::Debug, path::PathBuf, sync::OnceLock};
+
+use crate::{ConfigError, FlatValue};
+
+use super::{flat_pool::RedactedSettings, FlatPool};
+
+#[derive(Debug, Clone)]
+struct Resolved {
+    value: FlatValue,
+    // The value embeds secret values.
+    secret: bool,
+}
+
+type Resolution = Result<Resolved, ConfigError>;
+
+/// ## InterpolatedFlatPool
+///
+/// This flat pool resolves references to other fields in text values of the given pool:
+///
+///  * `${name}` is replaced by the value of the field `name`, when the whole text is a reference
+///    the referenced value keeps its type,
+///  * `${name:-fallback}` uses `fallback` when `name` is missing or empty,
+///  * `$$` is a literal `$`.
+///
+/// References are resolved at lookup time against the whole pool, so wrapping a
+/// [LayeredFlatPool](super::LayeredFlatPool) makes a value overridden in a higher layer update
+/// the fields referencing it. A field whose references cannot be resolved (undefined references,
+/// cycles) behaves as a missing field: it is not listed by [FlatPool::keys] and [FlatPool::has]
+/// is false. [FlatPool::require] returns the interpolation error of such a field and
+/// [check](InterpolatedFlatPool::check) returns the errors of every field. A field is secret when
+/// it references a secret field.
+///
+/// ```rust
+/// use flat_config::pool::{FlatPool, InterpolatedFlatPool, SimpleFlatPool};
+///
+/// let mut pool = SimpleFlatPool::default();
+/// pool.add("data_dir", "/srv/app".into())
+///     .add("log_dir", "${data_dir}/logs".into());
+/// let pool = InterpolatedFlatPool::new(pool);
+///
+/// assert_eq!(Some("/srv/app/logs".into()), pool.get("log_dir"));
+/// ```
+pub struct InterpolatedFlatPool<P: FlatPool> {
+    pool: P,
+    resolved: HashMap<String, OnceLock<Resolution>>,
+}
+
+impl<P: FlatPool> InterpolatedFlatPool<P> {
+    pub fn new(pool: P) -> Self {
+        let resolved = pool
+            .keys()
+            .into_iter()
+            .map(|key| (key, OnceLock::new()))
+            .collect();
+
+        Self { pool, resolved }
+    }
+
+    /// Resolve every field and report the interpolation errors, each one naming its field.
+    pub fn check(&self) -> Result<(), ConfigError> {
+        let errors: Vec<ConfigError> = self
+            .pool
+            .keys()
+            .iter()
+            .filter_map(|key| self.resolution(key)?.as_ref().err().cloned())
+            .collect();
+
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(ConfigError::Invalid(errors)),
+        }
+    }
+
+    fn resolution(&self, name: &str) -> Option<&Resolution> {
+        let cell = self.resolved.get(name)?;
+
+        if cell.get().is_none() {
+            let resolution = self.resolve(name, &mut Vec::new());
+            let _ = cell.set(resolution.map_err(|error| error.with_key(name)));
+        }
+
+        cell.get()
+    }
+
+    /// Resolve the given field, `stack` holds the fields being resolved to detect cycles. Errors
+    /// are attached to the field when they are cached so they name the field that was asked for.
+    fn resolve(&self, name: &str, stack: &mut Vec<String>) -> Resolution {
+        if let Some(resolution) = self.resolved.get(name).and_then(|cell| cell.get()) {
+            return resolution.clone();
+        }
+
+        if stack.iter().any(|key| key == name) {
+            return Err(ConfigError::IncorrectValue(format!(
+                "cyclic reference {} → {name}",
+                stack.join(" → ")
+            )));
+        }
+
+        let value = self.pool.get(name).ok_or_else(|| ConfigError::Missing {
+            field_name: name.to_string(),
+            fields: format!("{:?}", self.pool),
+        })?;
+        let secret = self.pool.is_secret(name);
+
+        let FlatValue::Text(text) = value else {
+            return Ok(Resolved { value, secret });
+        };
+
+        stack.push(name.to_string());
+        let resolution = self.interpolate(&text, stack).map(|resolved| Resolved {
+            value: resolved.value,
+            secret: secret || resolved.secret,
+        });
+        stack.pop();
+
+        if let Some(cell) = self.resolved.get(name) {
+            let _ = cell.set(resolution.clone().map_err(|error| error.with_key(name)));
+        }
+
+        resolution
+    }
+
+    /// Replace the references in the given text.
+    fn interpolate(&self, text: &str, stack: &mut Vec<String>) -> Resolution {
+        let mut output = String::new();
+        let mut secret = false;
+        let mut rest = text;
+
+        while let Some(position) = rest.find('$') {
+            output.push_str(&rest[..position]);
+            rest = &rest[position..];
+
+            if let Some(after) = rest.strip_prefix("$$") {
+                output.push('$');
+                rest = after;
+                continue;
+            }
+
+            let Some(after) = rest.strip_prefix("${") else {
+                output.push('$');
+                rest = &rest[1..];
+                continue;
+            };
+            let end = closing_brace(after).ok_or_else(|| {
+                ConfigError::IncorrectValue(format!("unclosed reference in '{text}'"))
+            })?;
+            let (name, fallback) = match after[..end].split_once(":-") {
+                Some((name, fallback)) => (name.trim(), Some(fallback)),
+                None => (after[..end].trim(), None),
+            };
+            let whole = output.is_empty() && after[end + 1..].is_empty();
+            rest = &after[end + 1..];
+
+            let referenced = match self.pool.has(name) {
+                true => Some(self.resolve(name, stack)?),
+                false => None,
+            };
+            let is_empty =
+                |resolved: &Resolved| matches!(&resolved.value, FlatValue::Text(t) if t.is_empty());
+            let referenced = match (referenced, fallback) {
+                (Some(resolved), Some(fallback)) if is_empty(&resolved) => {
+                    self.interpolate(fallback, stack)?
+                }
+                (Some(resolved), _) => resolved,
+                (None, Some(fallback)) => self.interpolate(fallback, stack)?,
+                (None, None) => {
+                    return Err(ConfigError::IncorrectValue(format!(
+                        "reference to the undefined field '{name}'"
+                    )))
+                }
+            };
+            secret = secret || referenced.secret;
+
+            // The text is a single reference, keep the type of the referenced value.
+            if whole {
+                return Ok(referenced);
+            }
+
+            match referenced.value {
+                FlatValue::Integer(i) => output.push_str(&i.to_string()),
+                FlatValue::Text(t) => output.push_str(&t),
+                FlatValue::Boolean(b) => output.push_str(&b.to_string()),
+            }
+        }
+        output.push_str(rest);
+
+        Ok(Resolved {
+            value: FlatValue::Text(output),
+            secret,
+        })
+    }
+}
+
+/// Position of the brace closing a reference, taking nested references into account.
+fn closing_brace(text: &str) -> Option<usize> {
+    let mut depth = 0;
+
+    for (position, c) in text.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' if depth == 0 => return Some(position),
+            '}' => depth -= 1,
+            _ => {}
+        }
+    }
+
+    None
+}
+
+impl<P: FlatPool> Debug for InterpolatedFlatPool<P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InterpolatedFlatPool")
+            .field("settings", &RedactedSettings(self))
+            .finish()
+    }
+}
+
+impl<P: FlatPool> FlatPool for InterpolatedFlatPool<P> {
+    fn get(&self, name: &str) -> Option<FlatValue> {
+        self.get_borrow(name).cloned()
+    }
+
+    fn get_borrow(&self, name: &str) -> Option<&FlatValue> {
+        match self.resolution(name)? {
+            Ok(resolved) => Some(&resolved.value),
+            Err(_) => None,
+        }
+    }
+
+    fn require(&self, name: &str) -> Result<FlatValue, ConfigError> {
+        match self.resolution(name) {
+            Some(Ok(resolved)) => Ok(resolved.value.clone()),
+            Some(Err(error)) => Err(error.clone()),
+            None => Err(ConfigError::Missing {
+                field_name: name.to_string(),
+                fields: format!("{self:?}"),
+            }),
+        }
+    }
+
+    fn unwrap(&self, name: &str) -> FlatValue {
+        self.require(name)
+            .map_err(|e| panic!("This should never happen: {e}"))
+            .unwrap()
+    }
+
+    fn get_or(&self, name: &str, default: FlatValue) -> FlatValue {
+        self.get(name).unwrap_or(default)
+    }
+
+    fn has(&self, name: &str) -> bool {
+        matches!(self.resolution(name), Some(Ok(_)))
+    }
+
+    fn keys(&self) -> Vec<String> {
+        self.pool
+            .keys()
+            .into_iter()
+            .filter(|key| self.has(key))
+            .collect()
+    }
+
+    fn is_secret(&self, name: &str) -> bool {
+        self.pool.is_secret(name)
+            || matches!(self.resolution(name), Some(Ok(resolved)) if resolved.secret)
+    }
+
+    fn source_files(&self) -> Vec<PathBuf> {
+        self.pool.source_files()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pool::{LayeredFlatPool, SimpleFlatPool};
+
+    fn init_pool() -> SimpleFlatPool {
+        let mut pool = SimpleFlatPool::default();
+        pool.add("data_dir", "/srv/app".into())
+            .add("log_dir", "${data_dir}/logs".into())
+            .add("cache_dir", "${ data_dir }/cache".into())
+            .add("base_port", 8000.into())
+            .add("port", "${base_port}".into())
+            .add("url", "http://localhost:${base_port}/".into());
+
+        pool
+    }
+
+    #[test]
+    fn references() {
+        let pool = InterpolatedFlatPool::new(init_pool());
+
+        assert_eq!(Some("/srv/app/logs".into()), pool.get("log_dir"));
+        assert_eq!(Some("/srv/app/cache".into()), pool.get("cache_dir"));
+        assert_eq!(Some(FlatValue::Integer(8000)), pool.get("port"));
+        assert_eq!(Some("http://localhost:8000/".into()), pool.get("url"));
+        assert_eq!(
+            Some(&FlatValue::from("/srv/app")),
+            pool.get_borrow("data_dir")
+        );
+    }
+
+    #[test]
+    fn layered_override() {
+        let mut parameter_pool = SimpleFlatPool::default();
+        parameter_pool.add("data_dir", "/data".into());
+        let pool = InterpolatedFlatPool::new(LayeredFlatPool::new(vec![
+            Box::new(init_pool()),
+            Box::new(parameter_pool),
+        ]));
+
+        assert_eq!(Some("/data/logs".into()), pool.get("log_dir"));
+    }
+
+    #[test]
+    fn escape_and_fallback() {
+        let mut pool = SimpleFlatPool::default();
+        pool.add("price", "$$5 and $HOME".into())
+            .add("literal", "$${data_dir}".into())
+            .add("empty", "".into())
+            .add("user", "${login:-nobody}".into())
+            .add("shell", "${empty:-/bin/sh}".into())
+            .add("nested", "${login:-${shell}}".into());
+        let pool = InterpolatedFlatPool::new(pool);
+
+        assert_eq!(Some("$5 and $HOME".into()), pool.get("price"));
+        assert_eq!(Some("${data_dir}".into()), pool.get("literal"));
+        assert_eq!(Some("nobody".into()), pool.get("user"));
+        assert_eq!(Some("/bin/sh".into()), pool.get("shell"));
+        assert_eq!(Some("/bin/sh".into()), pool.get("nested"));
+    }
+
+    #[test]
+    fn errors() {
+        let mut pool = SimpleFlatPool::default();
+        pool.add("a", "${b}".into())
+            .add("b", "x${c}".into())
+            .add("c", "${a}".into())
+            .add("undefined", "${nope}".into())
+            .add("unclosed", "${a".into());
+        let pool = InterpolatedFlatPool::new(pool);
+
+        let error = pool.require("a").unwrap_err();
+        assert!(
+            matches!(error, ConfigError::Setting { ref key, .. } if key == "a"),
+            "{error:?}"
+        );
+        assert!(format!("{error}").contains("a → b → c → a"));
+        assert!(pool.require("b").is_err());
+        assert_eq!(None, pool.get("c"));
+        assert!(!pool.has("c"));
+        assert!(pool.keys().is_empty());
+
+        assert!(pool.require("undefined").is_err());
+        assert!(pool.require("unclosed").is_err());
+        assert!(matches!(
+            pool.require("unexistent"),
+            Err(ConfigError::Missing { .. })
+        ));
+    }
+
+    #[test]
+    fn check() {
+        let mut pool = init_pool();
+        pool.add("a", "${b}".into())
+            .add("b", "${a}".into())
+            .add("undefined", "${nope}/logs".into());
+        let pool = InterpolatedFlatPool::new(pool);
+
+        let Err(ConfigError::Invalid(errors)) = pool.check() else {
+            panic!("interpolation errors should be reported");
+        };
+        let keys: Vec<&str> = errors
+            .iter()
+            .filter_map(|error| match error {
+                ConfigError::Setting { key, .. } => Some(key.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(vec!["a", "b", "undefined"], keys);
+        assert_eq!(init_pool().keys(), pool.keys());
+        assert!(InterpolatedFlatPool::new(init_pool()).check().is_ok());
+    }
+
+    #[test]
+    fn secrets() {
+        let mut pool = SimpleFlatPool::default();
+        pool.add_secret("password", "hunter2".into())
+            .add("url", "postgres://app:${password}@db/app".into());
+        let pool = InterpolatedFlatPool::new(pool);
+
+        assert!(pool.is_secret("url"));
+        assert_eq!(
+            Some("postgres://app:hunter2@db/app".into()),
+            pool.get("url")
+        );
+        assert!(!format!("{pool:?}").contains("hunter2"));
+    }
+}
@@ -1,20 +1,47 @@
-use crate::{ConfigError, FlatValue};
+use std::{collections::BTreeSet, fmt::Debug, path::PathBuf};
 
-use super::FlatPool;
+use crate::{ConfigError, FlatValue, SecretKeys};
 
-type Layer = Box<dyn FlatPool>;
+use super::{flat_pool::RedactedSettings, FlatPool};
+
+/// Layers are `Send + Sync`, so a layered pool can be shared between threads.
+pub type Layer = Box<dyn FlatPool + Send + Sync>;
 
 /// ## LayeredFlatPool
 ///
 /// This flat pool can take several sources and use a path to determine which data override others.
-#[derive(Debug)]
+/// A field is secret when any layer considers it secret.
+///
+/// Layers must be `Send + Sync`, so a layered pool can be put in an `Arc` and read from several
+/// threads. A [SnapshotFlatPool](super::SnapshotFlatPool) is a cheaper copy to hand out to
+/// worker threads.
 pub struct LayeredFlatPool {
     layers: Vec<Layer>,
+    secrets: SecretKeys,
 }
 
 impl LayeredFlatPool {
     pub fn new(layers: Vec<Layer>) -> Self {
-        Self { layers }
+        Self {
+            layers,
+            secrets: SecretKeys::default(),
+        }
+    }
+
+    /// Mark the fields matching the given pattern as secret whatever layer they come from, the
+    /// pattern may contain `*` wildcards like `*password*`.
+    pub fn mark_secret(&mut self, pattern: &str) -> &mut Self {
+        self.secrets.add(pattern);
+
+        self
+    }
+}
+
+impl Debug for LayeredFlatPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LayeredFlatPool")
+            .field("settings", &RedactedSettings(self))
+            .finish()
     }
 }
 
@@ -48,6 +75,26 @@ impl FlatPool for LayeredFlatPool {
             .rev()
             .find_map(|layer| layer.get_borrow(name))
     }
+
+    fn keys(&self) -> Vec<String> {
+        self.layers
+            .iter()
+            .flat_map(|layer| layer.keys())
+            .collect::<BTreeSet<String>>()
+            .into_iter()
+            .collect()
+    }
+
+    fn is_secret(&self, name: &str) -> bool {
+        self.secrets.matches(name) || self.layers.iter().any(|layer| layer.is_secret(name))
+    }
+
+    fn source_files(&self) -> Vec<PathBuf> {
+        self.layers
+            .iter()
+            .flat_map(|layer| layer.source_files())
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -113,6 +160,38 @@ mod tests {
         assert!(pool.has("setting_2"));
     }
 
+    #[test]
+    fn keys() {
+        let (pool1, pool2) = init_pools();
+        let pool = LayeredFlatPool::new(vec![Box::new(pool1), Box::new(pool2)]);
+
+        assert_eq!(
+            vec!["setting_1", "setting_2", "shared_setting"],
+            pool.keys()
+        );
+    }
+
+    #[test]
+    fn secrets() {
+        let (mut pool1, mut pool2) = init_pools();
+        pool1.add_secret("password", "first".into());
+        pool2
+            .add("password", "hunter2".into())
+            .add("token", "t".into());
+        let mut pool = LayeredFlatPool::new(vec![Box::new(pool1), Box::new(pool2)]);
+        pool.mark_secret("tok*");
+
+        assert!(pool.is_secret("password"));
+        assert!(pool.is_secret("token"));
+        assert!(!pool.is_secret("setting_1"));
+        assert_eq!(FlatValue::from("hunter2"), pool.unwrap("password"));
+
+        let debug = format!("{pool:?}");
+        assert!(!debug.contains("hunter2"));
+        assert!(!debug.contains("\"t\""));
+        assert!(debug.contains("\"setting_1\": Integer(9)"));
+    }
+
     #[test]
     fn get_instance() {
         let (pool1, pool2) = init_pools();
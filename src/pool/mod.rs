@@ -1,7 +1,25 @@
+mod aliased_flat_pool;
+mod args_flat_pool;
+mod env_flat_pool;
+mod file_flat_pool;
 mod flat_pool;
+mod include_flat_pool;
+mod interpolated_flat_pool;
 mod layered_flat_pool;
+mod normalized_flat_pool;
+mod profiled_flat_pool;
 mod simple_flat_pool;
+mod snapshot_flat_pool;
 
+pub use aliased_flat_pool::{AliasedFlatPool, Deprecation};
+pub use args_flat_pool::ArgsFlatPool;
+pub use env_flat_pool::EnvFlatPool;
+pub use file_flat_pool::FileFlatPool;
 pub use flat_pool::FlatPool;
-pub use layered_flat_pool::LayeredFlatPool;
+pub use include_flat_pool::{IncludeFlatPool, MAX_INCLUDE_DEPTH};
+pub use interpolated_flat_pool::InterpolatedFlatPool;
+pub use layered_flat_pool::{Layer, LayeredFlatPool};
+pub use normalized_flat_pool::{KeyCollision, NormalizedFlatPool};
+pub use profiled_flat_pool::{ProfiledFlatPool, Provenance};
 pub use simple_flat_pool::SimpleFlatPool;
+pub use snapshot_flat_pool::SnapshotFlatPool;
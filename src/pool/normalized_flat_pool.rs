@@ -0,0 +1,236 @@
+use std::{collections::BTreeMap, fmt::Debug, fmt::Display, path::PathBuf};
+
+use crate::{ConfigError, FlatValue, KeyNormalizer};
+
+use super::{flat_pool::RedactedSettings, FlatPool};
+
+/// ## KeyCollision
+///
+/// Several keys of a pool normalizing to the same name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyCollision {
+    pub key: String,
+    pub raw_keys: Vec<String>,
+}
+
+impl Display for KeyCollision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let raw_keys: Vec<String> = self.raw_keys.iter().map(|key| format!("'{key}'")).collect();
+
+        write!(
+            f,
+            "keys {} all normalize to '{}'",
+            raw_keys.join(", "),
+            self.key
+        )
+    }
+}
+
+/// ## NormalizedFlatPool
+///
+/// This flat pool exposes the settings of another pool under their normalized names and
+/// normalizes the names it is asked for, so `database-dir`, `DATABASE_DIR` and `database_dir`
+/// are the same setting with a [KeyNormalizer::standard] policy. Wrap each source with the same
+/// normalizer before layering them, so layers override each other whatever the key spelling.
+///
+/// When several keys of the inner pool normalize to the same name, the first one in sorted order
+/// is used. Such collisions are listed by [collisions](NormalizedFlatPool::collisions) and
+/// turned into errors by [check](NormalizedFlatPool::check).
+///
+/// ```rust
+/// use flat_config::{KeyNormalizer, pool::{FlatPool, NormalizedFlatPool, SimpleFlatPool}};
+///
+/// let mut pool = SimpleFlatPool::default();
+/// pool.add("DATABASE-DIR", "/var/lib/app".into());
+/// let pool = NormalizedFlatPool::new(pool, KeyNormalizer::standard());
+///
+/// assert_eq!(Some("/var/lib/app".into()), pool.get("database_dir"));
+/// assert_eq!(vec!["database_dir"], pool.keys());
+/// ```
+pub struct NormalizedFlatPool<P: FlatPool> {
+    pool: P,
+    normalizer: KeyNormalizer,
+    index: BTreeMap<String, Vec<String>>,
+}
+
+impl<P: FlatPool> NormalizedFlatPool<P> {
+    pub fn new(pool: P, normalizer: KeyNormalizer) -> Self {
+        let mut index: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+        for raw_key in pool.keys() {
+            index
+                .entry(normalizer.normalize(&raw_key))
+                .or_default()
+                .push(raw_key);
+        }
+
+        Self {
+            pool,
+            normalizer,
+            index,
+        }
+    }
+
+    /// Normalization policy of this pool.
+    pub fn normalizer(&self) -> &KeyNormalizer {
+        &self.normalizer
+    }
+
+    /// Keys of the inner pool normalizing to the same name, sorted by name.
+    pub fn collisions(&self) -> Vec<KeyCollision> {
+        self.index
+            .iter()
+            .filter(|(_, raw_keys)| raw_keys.len() > 1)
+            .map(|(key, raw_keys)| KeyCollision {
+                key: key.clone(),
+                raw_keys: raw_keys.clone(),
+            })
+            .collect()
+    }
+
+    /// Report every collision as an error naming the colliding keys.
+    pub fn check(&self) -> Result<(), ConfigError> {
+        let errors: Vec<ConfigError> = self
+            .collisions()
+            .into_iter()
+            .map(|collision| ConfigError::Constraint {
+                message: collision.to_string(),
+                keys: collision.raw_keys,
+            })
+            .collect();
+
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(ConfigError::Invalid(errors)),
+        }
+    }
+
+    /// Key of the inner pool holding the given setting.
+    fn raw_key(&self, name: &str) -> Option<&String> {
+        self.index.get(&self.normalizer.normalize(name))?.first()
+    }
+}
+
+impl<P: FlatPool> Debug for NormalizedFlatPool<P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NormalizedFlatPool")
+            .field("settings", &RedactedSettings(self))
+            .finish()
+    }
+}
+
+impl<P: FlatPool> FlatPool for NormalizedFlatPool<P> {
+    fn get(&self, name: &str) -> Option<FlatValue> {
+        self.get_borrow(name).cloned()
+    }
+
+    fn get_borrow(&self, name: &str) -> Option<&FlatValue> {
+        self.pool.get_borrow(self.raw_key(name)?)
+    }
+
+    fn require(&self, name: &str) -> Result<FlatValue, ConfigError> {
+        self.get(name).ok_or_else(|| ConfigError::Missing {
+            field_name: name.to_string(),
+            fields: format!("{self:?}"),
+        })
+    }
+
+    fn unwrap(&self, name: &str) -> FlatValue {
+        self.require(name)
+            .map_err(|e| panic!("This should never happen: {e}"))
+            .unwrap()
+    }
+
+    fn get_or(&self, name: &str, default: FlatValue) -> FlatValue {
+        self.get(name).unwrap_or(default)
+    }
+
+    fn has(&self, name: &str) -> bool {
+        self.raw_key(name).is_some()
+    }
+
+    fn keys(&self) -> Vec<String> {
+        self.index.keys().cloned().collect()
+    }
+
+    fn is_secret(&self, name: &str) -> bool {
+        self.index
+            .get(&self.normalizer.normalize(name))
+            .is_some_and(|raw_keys| raw_keys.iter().any(|key| self.pool.is_secret(key)))
+    }
+
+    fn source_files(&self) -> Vec<PathBuf> {
+        self.pool.source_files()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pool::{LayeredFlatPool, SimpleFlatPool};
+
+    fn init_pool() -> NormalizedFlatPool<SimpleFlatPool> {
+        let mut pool = SimpleFlatPool::default();
+        pool.add("Database-Dir", "/var/lib/app".into())
+            .add("SERVER__PORT", 8080.into())
+            .add("log_level", "info".into())
+            .add("LOG-LEVEL", "debug".into())
+            .add_secret("Password", "hunter2".into());
+
+        NormalizedFlatPool::new(pool, KeyNormalizer::standard().map_separator("__", "."))
+    }
+
+    #[test]
+    fn lookups() {
+        let pool = init_pool();
+
+        assert_eq!(Some("/var/lib/app".into()), pool.get("database_dir"));
+        assert_eq!(Some("/var/lib/app".into()), pool.get("DATABASE-DIR"));
+        assert_eq!(FlatValue::Integer(8080), pool.unwrap("server.port"));
+        assert!(pool.has("server__port"));
+        assert!(pool.is_secret("password"));
+        assert!(!format!("{pool:?}").contains("hunter2"));
+        assert!(matches!(
+            pool.require("database.dir"),
+            Err(ConfigError::Missing { .. })
+        ));
+        assert_eq!(
+            vec!["database_dir", "log_level", "password", "server.port"],
+            pool.keys()
+        );
+    }
+
+    #[test]
+    fn collisions() {
+        let pool = init_pool();
+
+        assert_eq!(Some("debug".into()), pool.get("log_level"));
+        assert_eq!(
+            vec![KeyCollision {
+                key: "log_level".to_string(),
+                raw_keys: vec!["LOG-LEVEL".to_string(), "log_level".to_string()],
+            }],
+            pool.collisions()
+        );
+        assert_eq!(
+            "CONFIGURATION ERROR: 1 invalid settings:\n  - Settings 'LOG-LEVEL', 'log_level': keys 'LOG-LEVEL', 'log_level' all normalize to 'log_level'.",
+            pool.check().unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn layers() {
+        let normalizer = KeyNormalizer::standard();
+        let mut file = SimpleFlatPool::default();
+        file.add("database_dir", "/var/lib/app".into());
+        let mut env = SimpleFlatPool::default();
+        env.add("DATABASE_DIR", "/srv/app".into());
+        let pool = LayeredFlatPool::new(vec![
+            Box::new(NormalizedFlatPool::new(file, normalizer.clone())),
+            Box::new(NormalizedFlatPool::new(env, normalizer)),
+        ]);
+
+        assert_eq!(Some("/srv/app".into()), pool.get("database-dir"));
+        assert_eq!(vec!["database_dir"], pool.keys());
+    }
+}
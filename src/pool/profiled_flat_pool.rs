@@ -0,0 +1,292 @@
+use std::{collections::BTreeSet, fmt::Debug, fmt::Display, path::PathBuf};
+
+use crate::{ConfigError, FlatValue};
+
+use super::{flat_pool::RedactedSettings, FlatPool};
+
+/// Profile and setting name of a key scoped to a profile, either `profile.<profile>.<name>` or
+/// `<name>@<profile>`.
+fn scoped(key: &str) -> Option<(&str, &str)> {
+    match key.strip_prefix("profile.") {
+        Some(rest) => rest.split_once('.'),
+        None => key.rsplit_once('@').map(|(name, profile)| (profile, name)),
+    }
+    .filter(|(profile, name)| !profile.is_empty() && !name.is_empty())
+}
+
+/// ## Provenance
+///
+/// Key of the inner pool a setting value was read from, with the profile it belongs to when the
+/// value overrides the base one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Provenance {
+    pub key: String,
+    pub profile: Option<String>,
+}
+
+impl Display for Provenance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "'{}'", self.key)?;
+
+        match &self.profile {
+            Some(profile) => write!(f, " from profile '{profile}'"),
+            None => Ok(()),
+        }
+    }
+}
+
+/// ## ProfiledFlatPool
+///
+/// This flat pool lets one source hold the settings of several profiles (`dev`, `staging`,
+/// `prod`…). Settings scoped to a profile are written in `[profile.prod]` sections, giving
+/// `profile.prod.<name>` keys, or with the `<name>@prod` convention. The values of the active
+/// profile override the base values, the other profiles are ignored. Scoped keys are not
+/// settings by themselves, they are neither listed nor read.
+///
+/// The active profile is chosen with [activate](ProfiledFlatPool::activate),
+/// [activate_from_env](ProfiledFlatPool::activate_from_env) or
+/// [activate_from_setting](ProfiledFlatPool::activate_from_setting): the last one finding a
+/// profile wins, so call them from the lowest to the highest precedence.
+///
+/// ```rust
+/// use flat_config::pool::{FlatPool, ProfiledFlatPool, SimpleFlatPool};
+///
+/// let mut pool = SimpleFlatPool::default();
+/// pool.add("profile", "prod".into())
+///     .add("log.level", "debug".into())
+///     .add("profile.prod.log.level", "warn".into())
+///     .add("workers@prod", 16.into());
+/// let mut pool = ProfiledFlatPool::new(pool);
+/// pool.activate_from_setting("profile");
+///
+/// assert_eq!(Some("prod"), pool.profile());
+/// assert_eq!(Some("warn".into()), pool.get("log.level"));
+/// assert_eq!(Some(16.into()), pool.get("workers"));
+/// assert_eq!(
+///     "'profile.prod.log.level' from profile 'prod'",
+///     pool.provenance("log.level").unwrap().to_string()
+/// );
+/// ```
+pub struct ProfiledFlatPool<P: FlatPool> {
+    pool: P,
+    profile: Option<String>,
+}
+
+impl<P: FlatPool> ProfiledFlatPool<P> {
+    /// Wrap the given pool, no profile is active.
+    pub fn new(pool: P) -> Self {
+        Self {
+            pool,
+            profile: None,
+        }
+    }
+
+    /// Activate the given profile.
+    pub fn activate(&mut self, profile: &str) -> &mut Self {
+        self.profile = Some(profile.to_string());
+
+        self
+    }
+
+    /// Activate the profile named by the given environment variable, if it is set.
+    pub fn activate_from_env(&mut self, var: &str) -> &mut Self {
+        match std::env::var(var) {
+            Ok(profile) if !profile.trim().is_empty() => self.activate(profile.trim()),
+            _ => self,
+        }
+    }
+
+    /// Activate the profile named by the given setting of the base values, if it is present.
+    pub fn activate_from_setting(&mut self, key: &str) -> &mut Self {
+        match self.pool.get(key) {
+            Some(FlatValue::Text(profile)) if !profile.trim().is_empty() => {
+                self.activate(profile.trim())
+            }
+            _ => self,
+        }
+    }
+
+    /// Active profile, if any.
+    pub fn profile(&self) -> Option<&str> {
+        self.profile.as_deref()
+    }
+
+    /// Profiles having settings in the pool, sorted.
+    pub fn profiles(&self) -> Vec<String> {
+        self.pool
+            .keys()
+            .iter()
+            .filter_map(|key| scoped(key).map(|(profile, _)| profile.to_string()))
+            .collect::<BTreeSet<String>>()
+            .into_iter()
+            .collect()
+    }
+
+    /// Where the value of the given setting is read from.
+    pub fn provenance(&self, name: &str) -> Option<Provenance> {
+        if scoped(name).is_some() {
+            return None;
+        }
+
+        if let Some(profile) = &self.profile {
+            for key in [
+                format!("profile.{profile}.{name}"),
+                format!("{name}@{profile}"),
+            ] {
+                if self.pool.has(&key) {
+                    return Some(Provenance {
+                        key,
+                        profile: Some(profile.clone()),
+                    });
+                }
+            }
+        }
+
+        self.pool.has(name).then(|| Provenance {
+            key: name.to_string(),
+            profile: None,
+        })
+    }
+}
+
+impl<P: FlatPool> Debug for ProfiledFlatPool<P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProfiledFlatPool")
+            .field("profile", &self.profile)
+            .field("settings", &RedactedSettings(self))
+            .finish()
+    }
+}
+
+impl<P: FlatPool> FlatPool for ProfiledFlatPool<P> {
+    fn get(&self, name: &str) -> Option<FlatValue> {
+        self.get_borrow(name).cloned()
+    }
+
+    fn get_borrow(&self, name: &str) -> Option<&FlatValue> {
+        self.pool.get_borrow(&self.provenance(name)?.key)
+    }
+
+    fn require(&self, name: &str) -> Result<FlatValue, ConfigError> {
+        self.get(name).ok_or_else(|| ConfigError::Missing {
+            field_name: name.to_string(),
+            fields: format!("{self:?}"),
+        })
+    }
+
+    fn unwrap(&self, name: &str) -> FlatValue {
+        self.require(name)
+            .map_err(|e| panic!("This should never happen: {e}"))
+            .unwrap()
+    }
+
+    fn get_or(&self, name: &str, default: FlatValue) -> FlatValue {
+        self.get(name).unwrap_or(default)
+    }
+
+    fn has(&self, name: &str) -> bool {
+        self.provenance(name).is_some()
+    }
+
+    fn keys(&self) -> Vec<String> {
+        self.pool
+            .keys()
+            .iter()
+            .filter_map(|key| match scoped(key) {
+                Some((profile, name)) => {
+                    (Some(profile) == self.profile.as_deref()).then(|| name.to_string())
+                }
+                None => Some(key.clone()),
+            })
+            .collect::<BTreeSet<String>>()
+            .into_iter()
+            .collect()
+    }
+
+    fn is_secret(&self, name: &str) -> bool {
+        self.pool.is_secret(name)
+            || self
+                .provenance(name)
+                .is_some_and(|provenance| self.pool.is_secret(&provenance.key))
+    }
+
+    fn source_files(&self) -> Vec<PathBuf> {
+        self.pool.source_files()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{pool::SimpleFlatPool, FileFormat};
+
+    fn init_pool() -> ProfiledFlatPool<SimpleFlatPool> {
+        let content = r#"
+            log_level = "info"
+            workers = 4
+            workers@dev = 1
+            password = "base"
+
+            [profile.prod]
+            log_level = "warn"
+            password = "hunter2"
+
+            [profile.staging]
+            log_level = "debug"
+        "#;
+        let mut pool = FileFormat::Ini.parse("app.ini", content).unwrap();
+        pool.mark_secret("*password");
+
+        ProfiledFlatPool::new(pool)
+    }
+
+    #[test]
+    fn no_profile() {
+        let pool = init_pool();
+
+        assert_eq!(None, pool.profile());
+        assert_eq!(Some("info".into()), pool.get("log_level"));
+        assert_eq!(Some(4.into()), pool.get("workers"));
+        assert!(!pool.has("profile.prod.log_level"));
+        assert!(!pool.has("workers@dev"));
+        assert_eq!(vec!["log_level", "password", "workers"], pool.keys());
+        assert_eq!(vec!["dev", "prod", "staging"], pool.profiles());
+    }
+
+    #[test]
+    fn overrides() {
+        let mut pool = init_pool();
+        pool.activate("prod");
+
+        assert_eq!(Some("warn".into()), pool.get("log_level"));
+        assert_eq!(Some(4.into()), pool.get("workers"));
+        assert!(pool.is_secret("password"));
+        assert!(!format!("{pool:?}").contains("hunter2"));
+        assert_eq!(
+            Some(Provenance {
+                key: "workers".to_string(),
+                profile: None
+            }),
+            pool.provenance("workers")
+        );
+
+        pool.activate("dev");
+        assert_eq!(Some(1.into()), pool.get("workers"));
+        assert_eq!(
+            "'workers@dev' from profile 'dev'",
+            pool.provenance("workers").unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn activation() {
+        let mut pool = init_pool();
+        pool.activate_from_setting("log_level")
+            .activate_from_env("FLAT_CONFIG_TEST_UNSET_PROFILE");
+
+        assert_eq!(Some("info"), pool.profile());
+
+        pool.activate_from_env("CARGO_PKG_NAME");
+        assert_eq!(Some("flat_config"), pool.profile());
+    }
+}
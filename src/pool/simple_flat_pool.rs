@@ -1,35 +1,104 @@
-use std::collections::HashMap;
+use std::{borrow::Cow, collections::HashMap, fmt::Debug};
 
-use crate::{ConfigError, FlatValue};
+use crate::{ConfigError, FlatValue, KeyPath, SecretKeys};
 
-use super::FlatPool;
+use super::{flat_pool::RedactedSettings, FlatPool};
 
 /// ## Configuration Settings Pool
 ///
 /// This contains flat values.
-#[derive(Debug, Default)]
+#[derive(Default)]
 pub struct SimpleFlatPool {
     settings: HashMap<String, FlatValue>,
+    secrets: SecretKeys,
 }
 
 impl SimpleFlatPool {
-    /// Add or replace a value in the pool.
+    /// Add or replace a value in the pool. Keys are stored in their canonical [KeyPath] form and
+    /// looked up the same way, `servers[0]` and `servers.0` are the same setting. Malformed keys
+    /// are stored as is and reported by [check_keys](FlatPool::check_keys).
     pub fn add(&mut self, name: &str, value: FlatValue) -> &mut Self {
-        self.settings.insert(name.to_string(), value);
+        self.settings.insert(canonical(name).into_owned(), value);
 
         self
     }
+
+    /// Add or replace a value in the pool, failing when the key is not a valid [KeyPath].
+    pub fn try_add(&mut self, name: &str, value: FlatValue) -> Result<&mut Self, ConfigError> {
+        let key = KeyPath::parse(name)?;
+
+        Ok(self.add(&key, value))
+    }
+
+    /// Add or replace a secret value in the pool.
+    pub fn add_secret(&mut self, name: &str, value: FlatValue) -> &mut Self {
+        self.secrets.add(&canonical(name));
+
+        self.add(name, value)
+    }
+
+    /// Mark the fields matching the given pattern as secret, the pattern may contain `*`
+    /// wildcards like `*password*`.
+    pub fn mark_secret(&mut self, pattern: &str) -> &mut Self {
+        self.secrets.add(pattern);
+
+        self
+    }
+
+    /// Mark all the fields of this pool as secret, this is useful when the source of the pool
+    /// only holds secrets.
+    pub fn mark_all_secret(&mut self) -> &mut Self {
+        self.secrets.add_all();
+
+        self
+    }
+
+    /// Copy the settings of the given pool, secret settings stay secret.
+    pub(crate) fn copy_of(pool: &(impl FlatPool + ?Sized)) -> Self {
+        let mut copy = Self::default();
+
+        for key in pool.keys() {
+            let Some(value) = pool.get(&key) else {
+                continue;
+            };
+
+            match pool.is_secret(&key) {
+                true => copy.add_secret(&key, value),
+                false => copy.add(&key, value),
+            };
+        }
+
+        copy
+    }
+}
+
+/// Canonical form of a valid key, only keys with bracketed indices differ from it.
+fn canonical(name: &str) -> Cow<'_, str> {
+    match name.contains('[') {
+        true => KeyPath::parse(name)
+            .map(|key| Cow::Owned(key.to_string()))
+            .unwrap_or(Cow::Borrowed(name)),
+        false => Cow::Borrowed(name),
+    }
+}
+
+impl Debug for SimpleFlatPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SimpleFlatPool")
+            .field("settings", &RedactedSettings(self))
+            .finish()
+    }
 }
 
 impl FlatPool for SimpleFlatPool {
     /// Return a cloned value if present.
     fn get(&self, name: &str) -> Option<FlatValue> {
-        self.settings.get(name).cloned()
+        self.get_borrow(name).cloned()
     }
 
     /// Get a borrowed value from the pool if present.
     fn get_borrow(&self, name: &str) -> Option<&FlatValue> {
-        self.settings.get(name)
+        self.settings.get(canonical(name).as_ref())
     }
 
     /// Require the field to be present or return an error. This will prevent the builder to create
@@ -57,7 +126,19 @@ impl FlatPool for SimpleFlatPool {
 
     /// Is this field present in the pool?
     fn has(&self, name: &str) -> bool {
-        self.settings.contains_key(name)
+        self.settings.contains_key(canonical(name).as_ref())
+    }
+
+    /// Names of all the fields present in the pool, sorted.
+    fn keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self.settings.keys().cloned().collect();
+        keys.sort();
+
+        keys
+    }
+
+    fn is_secret(&self, name: &str) -> bool {
+        self.secrets.matches(&canonical(name))
     }
 }
 
@@ -196,4 +277,78 @@ mod tests {
         assert!(pool.has("thing_a"));
         assert!(!pool.has("unexistent"));
     }
+
+    #[test]
+    fn keys() {
+        let mut pool = SimpleFlatPool::default();
+        pool.add("thing_a", "thing_a".into())
+            .add("how_much", 2.into())
+            .add("is_real", true.into());
+
+        assert_eq!(vec!["how_much", "is_real", "thing_a"], pool.keys());
+    }
+
+    #[test]
+    fn secrets() {
+        let mut pool = SimpleFlatPool::default();
+        pool.add("database.password", "hunter2".into())
+            .add_secret("api_key", "abcd".into())
+            .add("how_much", 2.into())
+            .mark_secret("*password*");
+
+        assert!(pool.is_secret("database.password"));
+        assert!(pool.is_secret("api_key"));
+        assert!(!pool.is_secret("how_much"));
+        assert_eq!(FlatValue::from("hunter2"), pool.unwrap("database.password"));
+
+        let message = format!("{}", pool.require("unexistent").unwrap_err());
+        assert!(!message.contains("hunter2"));
+        assert!(!message.contains("abcd"));
+        assert!(message.contains("\"database.password\": \"***\""));
+        assert!(message.contains("\"how_much\": Integer(2)"));
+    }
+
+    #[test]
+    fn check_keys() {
+        let mut pool = SimpleFlatPool::default();
+        pool.add("servers.0.port", 80.into());
+
+        assert!(pool.check_keys().is_ok());
+
+        pool.add("http..port", 80.into())
+            .add("log level", "info".into());
+        let error = pool.check_keys().unwrap_err();
+
+        assert!(matches!(error, ConfigError::Invalid(ref errors) if errors.len() == 2));
+    }
+
+    #[test]
+    fn canonical_keys() {
+        let mut pool = SimpleFlatPool::default();
+        pool.add("servers[0].port", 80.into())
+            .add("servers.1.port", 443.into());
+
+        assert_eq!(vec!["servers.0.port", "servers.1.port"], pool.keys());
+        assert_eq!(Some(80.into()), pool.get("servers[0].port"));
+        assert_eq!(Some(443.into()), pool.get("servers[1].port"));
+        assert!(pool.has("servers[0].port"));
+        assert!(pool.require("servers[1].port").is_ok());
+
+        assert!(pool.try_add("servers[2].port", 8080.into()).is_ok());
+        assert_eq!(Some(8080.into()), pool.get("servers.2.port"));
+        assert!(pool.try_add("servers.01.port", 8081.into()).is_err());
+        assert!(pool.try_add("log level", "info".into()).is_err());
+        assert!(!pool.has("log level"));
+    }
+
+    #[test]
+    fn canonical_secrets() {
+        let mut pool = SimpleFlatPool::default();
+        pool.add_secret("db[0].password", "hunter2".into());
+
+        assert!(pool.is_secret("db.0.password"));
+        assert!(pool.is_secret("db[0].password"));
+        assert!(!format!("{pool:?}").contains("hunter2"));
+        assert!(!format!("{}", pool.require("unexistent").unwrap_err()).contains("hunter2"));
+    }
 }
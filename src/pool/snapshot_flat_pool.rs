@@ -0,0 +1,207 @@
+use std::{fmt::Debug, path::PathBuf, sync::Arc};
+
+use crate::{ConfigError, FlatValue};
+
+use super::{flat_pool::RedactedSettings, FlatPool, SimpleFlatPool};
+
+#[derive(Default)]
+struct Snapshot {
+    pool: SimpleFlatPool,
+    source_files: Vec<PathBuf>,
+}
+
+/// ## SnapshotFlatPool
+///
+/// This flat pool is an immutable copy of the settings of another pool, taken once. Clones share
+/// the same settings, so a snapshot can be handed to every worker thread of a server and read
+/// concurrently without locking. Secret settings stay secret and the source files of the copied
+/// pool are kept.
+///
+/// ```rust
+/// use flat_config::pool::{FlatPool, LayeredFlatPool, SimpleFlatPool, SnapshotFlatPool};
+///
+/// let mut file_pool = SimpleFlatPool::default();
+/// file_pool.add("workers", 4.into());
+/// let snapshot = SnapshotFlatPool::new(&LayeredFlatPool::new(vec![Box::new(file_pool)]));
+///
+/// let handles: Vec<_> = (0..2)
+///     .map(|_| {
+///         let snapshot = snapshot.clone();
+///         std::thread::spawn(move || snapshot.get("workers"))
+///     })
+///     .collect();
+///
+/// for handle in handles {
+///     assert_eq!(Some(4.into()), handle.join().unwrap());
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct SnapshotFlatPool {
+    snapshot: Arc<Snapshot>,
+}
+
+impl SnapshotFlatPool {
+    /// Copy the current settings of the given pool.
+    pub fn new(pool: &(impl FlatPool + ?Sized)) -> Self {
+        Self {
+            snapshot: Arc::new(Snapshot {
+                pool: SimpleFlatPool::copy_of(pool),
+                source_files: pool.source_files(),
+            }),
+        }
+    }
+
+    /// Do both snapshots share the same settings?
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.snapshot, &other.snapshot)
+    }
+}
+
+impl From<SimpleFlatPool> for SnapshotFlatPool {
+    fn from(pool: SimpleFlatPool) -> Self {
+        Self {
+            snapshot: Arc::new(Snapshot {
+                pool,
+                source_files: Vec::new(),
+            }),
+        }
+    }
+}
+
+impl Debug for SnapshotFlatPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SnapshotFlatPool")
+            .field("files", &self.snapshot.source_files)
+            .field("settings", &RedactedSettings(self))
+            .finish()
+    }
+}
+
+impl FlatPool for SnapshotFlatPool {
+    fn get(&self, name: &str) -> Option<FlatValue> {
+        self.snapshot.pool.get(name)
+    }
+
+    fn get_borrow(&self, name: &str) -> Option<&FlatValue> {
+        self.snapshot.pool.get_borrow(name)
+    }
+
+    fn require(&self, name: &str) -> Result<FlatValue, ConfigError> {
+        self.get(name).ok_or_else(|| ConfigError::Missing {
+            field_name: name.to_string(),
+            fields: format!("{self:?}"),
+        })
+    }
+
+    fn unwrap(&self, name: &str) -> FlatValue {
+        self.require(name)
+            .map_err(|e| panic!("This should never happen: {e}"))
+            .unwrap()
+    }
+
+    fn get_or(&self, name: &str, default: FlatValue) -> FlatValue {
+        self.get(name).unwrap_or(default)
+    }
+
+    fn has(&self, name: &str) -> bool {
+        self.snapshot.pool.has(name)
+    }
+
+    fn keys(&self) -> Vec<String> {
+        self.snapshot.pool.keys()
+    }
+
+    fn is_secret(&self, name: &str) -> bool {
+        self.snapshot.pool.is_secret(name)
+    }
+
+    fn source_files(&self) -> Vec<PathBuf> {
+        self.snapshot.source_files.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pool::{
+        AliasedFlatPool, ArgsFlatPool, EnvFlatPool, FileFlatPool, IncludeFlatPool,
+        InterpolatedFlatPool, LayeredFlatPool, NormalizedFlatPool, ProfiledFlatPool,
+    };
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn pools_are_send_and_sync() {
+        assert_send_sync::<SimpleFlatPool>();
+        assert_send_sync::<FileFlatPool>();
+        assert_send_sync::<IncludeFlatPool>();
+        assert_send_sync::<EnvFlatPool>();
+        assert_send_sync::<ArgsFlatPool>();
+        assert_send_sync::<LayeredFlatPool>();
+        assert_send_sync::<AliasedFlatPool<LayeredFlatPool>>();
+        assert_send_sync::<InterpolatedFlatPool<LayeredFlatPool>>();
+        assert_send_sync::<NormalizedFlatPool<LayeredFlatPool>>();
+        assert_send_sync::<ProfiledFlatPool<LayeredFlatPool>>();
+        assert_send_sync::<SnapshotFlatPool>();
+    }
+
+    #[test]
+    fn snapshot() {
+        let mut base = SimpleFlatPool::default();
+        base.add("workers", 4.into())
+            .add("log.level", "info".into());
+        let mut secrets = SimpleFlatPool::default();
+        secrets.add_secret("password", "hunter2".into());
+        let layered = LayeredFlatPool::new(vec![Box::new(base), Box::new(secrets)]);
+
+        let snapshot = SnapshotFlatPool::new(&layered);
+        let shared = snapshot.clone();
+
+        assert!(snapshot.ptr_eq(&shared));
+        assert!(!snapshot.ptr_eq(&SnapshotFlatPool::new(&layered)));
+        assert_eq!(layered.keys(), shared.keys());
+        assert!(shared.is_secret("password"));
+        assert!(!format!("{shared:?}").contains("hunter2"));
+
+        let reader = shared.clone();
+        let workers = std::thread::spawn(move || reader.get("workers"))
+            .join()
+            .unwrap();
+        assert_eq!(Some(4.into()), workers);
+    }
+
+    #[test]
+    fn source_files() {
+        let path =
+            std::env::temp_dir().join(format!("flat_config_snapshot_{}.ini", std::process::id()));
+        std::fs::write(&path, "workers = 4\n").unwrap();
+        let snapshot = SnapshotFlatPool::new(&FileFlatPool::load(&path).unwrap());
+
+        assert_eq!(vec![path.clone()], snapshot.source_files());
+        assert!(format!("{snapshot:?}").contains(&format!("files: [{path:?}]")));
+    }
+
+    #[test]
+    fn env_layer() {
+        let mut defaults = SimpleFlatPool::default();
+        defaults
+            .add("server.port", 8080.into())
+            .add("server.host", "localhost".into());
+        let env = EnvFlatPool::from_vars(
+            "APP_",
+            [("APP_SERVER__PORT", "9090"), ("APP_LOG__LEVEL", "debug")],
+        );
+        let layered = LayeredFlatPool::new(vec![Box::new(defaults), Box::new(env)]);
+
+        let snapshot = SnapshotFlatPool::new(&layered);
+
+        assert_eq!(Some(9090.into()), snapshot.get("server.port"));
+        assert_eq!(
+            vec!["log.level", "server.host", "server.port"],
+            snapshot.keys()
+        );
+        for key in layered.keys() {
+            assert_eq!(layered.get(&key), snapshot.get(&key), "{key}");
+        }
+    }
+}
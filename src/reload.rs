@@ -0,0 +1,319 @@
+use std::{
+    path::PathBuf,
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc, Mutex, PoisonError, RwLock,
+    },
+    time::Duration,
+};
+
+use crate::{
+    pool::{FlatPool, SimpleFlatPool},
+    ConfigBuilder, ConfigError, FileWatcher, PoolDiff,
+};
+
+/// ## Configuration snapshot
+///
+/// Configuration built from the settings loaded at a given time, along with a copy of those
+/// settings.
+#[derive(Debug)]
+pub struct ConfigSnapshot<T> {
+    config: T,
+    pool: SimpleFlatPool,
+}
+
+impl<T> ConfigSnapshot<T> {
+    /// Typed configuration.
+    pub fn config(&self) -> &T {
+        &self.config
+    }
+
+    /// Settings the configuration was built from, secret settings stay secret.
+    pub fn pool(&self) -> &SimpleFlatPool {
+        &self.pool
+    }
+}
+
+/// ## Reload events
+///
+/// Sent to the subscribers of a [ReloadableConfig] after each reload:
+///
+///  * `Changed`: a new configuration was published, with the settings added, removed or changed,
+///  * `Failed`: the settings could not be loaded or the configuration could not be built, the
+///    previous configuration is kept.
+#[derive(Debug, Clone)]
+pub enum ReloadEvent {
+    Changed(PoolDiff),
+    Failed(ConfigError),
+}
+
+type Loaded<T> = (ConfigSnapshot<T>, Vec<PathBuf>);
+type Loader<T> = Box<dyn Fn() -> Result<Loaded<T>, ConfigError> + Send + Sync>;
+
+/// ## ReloadableConfig
+///
+/// Handle on a configuration that can be reloaded while the application runs. The loader
+/// function creates the pool, typically reading files with
+/// [FileFlatPool](crate::pool::FileFlatPool), and the builder creates the configuration from it.
+/// A reload publishes the new configuration only when it builds successfully, readers holding the
+/// previous snapshot keep it until they ask for the [current](ReloadableConfig::current) one.
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use flat_config::{ConfigBuilder, ConfigError, ReloadableConfig, TryUnwrap,
+///     pool::{FlatPool, SimpleFlatPool}};
+///
+/// struct PortBuilder;
+///
+/// impl ConfigBuilder<isize> for PortBuilder {
+///     fn build(&self, config_pool: &impl FlatPool) -> Result<isize, ConfigError> {
+///         config_pool.require("port")?.try_unwrap()
+///     }
+/// }
+///
+/// let config = Arc::new(ReloadableConfig::new(
+///     || {
+///         let mut pool = SimpleFlatPool::default();
+///         pool.add("port", 8080.into());
+///         Ok(pool)
+///     },
+///     PortBuilder,
+/// ).unwrap());
+///
+/// assert_eq!(&8080, config.current().config());
+/// assert!(config.reload().unwrap().is_empty());
+/// ```
+pub struct ReloadableConfig<T> {
+    loader: Loader<T>,
+    current: RwLock<Arc<ConfigSnapshot<T>>>,
+    files: Mutex<Vec<PathBuf>>,
+    subscribers: Mutex<Vec<Sender<ReloadEvent>>>,
+    // Serializes the reloads so a slow reload cannot overwrite a more recent one.
+    reloading: Mutex<()>,
+}
+
+impl<T: Send + Sync + 'static> ReloadableConfig<T> {
+    /// Load the settings and build the initial configuration, failing when they cannot be loaded
+    /// or built.
+    pub fn new<P, F, B>(loader: F, builder: B) -> Result<Self, ConfigError>
+    where
+        P: FlatPool,
+        F: Fn() -> Result<P, ConfigError> + Send + Sync + 'static,
+        B: ConfigBuilder<T> + Send + Sync + 'static,
+    {
+        let loader: Loader<T> = Box::new(move || {
+            let pool = loader()?;
+            let config = builder.build(&pool)?;
+            let snapshot = ConfigSnapshot {
+                config,
+                pool: SimpleFlatPool::copy_of(&pool),
+            };
+
+            Ok((snapshot, pool.source_files()))
+        });
+        let (snapshot, files) = loader()?;
+
+        Ok(Self {
+            loader,
+            current: RwLock::new(Arc::new(snapshot)),
+            files: Mutex::new(files),
+            subscribers: Mutex::new(Vec::new()),
+            reloading: Mutex::new(()),
+        })
+    }
+
+    /// Snapshot of the last configuration successfully loaded.
+    pub fn current(&self) -> Arc<ConfigSnapshot<T>> {
+        self.current
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Files the current settings were read from.
+    pub fn source_files(&self) -> Vec<PathBuf> {
+        self.files
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Load the settings again and publish the new configuration when it builds and differs from
+    /// the current one. Returns the settings that changed, subscribers are notified of the outcome.
+    pub fn reload(&self) -> Result<PoolDiff, ConfigError> {
+        let _reloading = self
+            .reloading
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        let (snapshot, files) = match (self.loader)() {
+            Ok(loaded) => loaded,
+            Err(error) => {
+                self.notify(ReloadEvent::Failed(error.clone()));
+
+                return Err(error);
+            }
+        };
+        *self.files.lock().unwrap_or_else(PoisonError::into_inner) = files;
+
+        let changed = PoolDiff::new(self.current().pool(), snapshot.pool());
+
+        if !changed.is_empty() {
+            *self.current.write().unwrap_or_else(PoisonError::into_inner) = Arc::new(snapshot);
+            self.notify(ReloadEvent::Changed(changed.clone()));
+        }
+
+        Ok(changed)
+    }
+
+    /// Receive an event after each reload.
+    pub fn subscribe(&self) -> Receiver<ReloadEvent> {
+        let (sender, receiver) = mpsc::channel();
+        self.subscribers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(sender);
+
+        receiver
+    }
+
+    /// Reload the configuration when one of its source files changes. The files are watched
+    /// until the returned watcher is dropped, `interval` is the polling period when file system
+    /// notifications are not available. Only the files read by the last load are watched, a file
+    /// created afterwards, like a new [drop-in](crate::DropIns), is read by the next reload.
+    pub fn watch(self: &Arc<Self>, interval: Duration) -> FileWatcher {
+        let files = Arc::downgrade(self);
+        let reloadable = Arc::downgrade(self);
+
+        FileWatcher::spawn(
+            move || files.upgrade().map(|config| config.source_files()),
+            interval,
+            move || {
+                if let Some(config) = reloadable.upgrade() {
+                    let _ = config.reload();
+                }
+            },
+        )
+    }
+
+    fn notify(&self, event: ReloadEvent) {
+        self.subscribers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .retain(|subscriber| subscriber.send(event.clone()).is_ok());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::{pool::FileFlatPool, TryUnwrap};
+
+    #[derive(Debug, PartialEq)]
+    struct ServerConfig {
+        port: isize,
+        name: String,
+    }
+
+    struct ServerConfigBuilder;
+
+    impl ConfigBuilder<ServerConfig> for ServerConfigBuilder {
+        fn build(&self, config_pool: &impl FlatPool) -> Result<ServerConfig, ConfigError> {
+            Ok(ServerConfig {
+                port: config_pool.require("server.port")?.try_unwrap()?,
+                name: config_pool.require("server.name")?.try_unwrap()?,
+            })
+        }
+    }
+
+    fn write_file(name: &str, content: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("flat_config_reload_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        fs::write(&path, content).unwrap();
+
+        path
+    }
+
+    fn reloadable(path: &std::path::Path) -> ReloadableConfig<ServerConfig> {
+        let path = path.to_path_buf();
+
+        ReloadableConfig::new(
+            move || {
+                let mut pool = FileFlatPool::load(&path)?;
+                pool.mark_secret("*password*");
+
+                Ok(pool)
+            },
+            ServerConfigBuilder,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn reload() {
+        let path = write_file(
+            "reload.conf",
+            "[server]\nport = 80\nname = web\npassword = hunter2\n",
+        );
+        let config = reloadable(&path);
+        let events = config.subscribe();
+
+        assert_eq!(vec![path.clone()], config.source_files());
+        assert_eq!(80, config.current().config().port);
+        assert!(config.current().pool().is_secret("server.password"));
+        assert!(config.reload().unwrap().is_empty());
+        assert!(events.try_recv().is_err());
+
+        let previous = config.current();
+        fs::write(&path, "[server]\nport = 8080\nname = web\nlog = info\n").unwrap();
+        let diff = config.reload().unwrap();
+
+        assert_eq!(
+            "+ server.log = \"info\"\n- server.password = ***\n~ server.port = 80 → 8080\n",
+            diff.to_string()
+        );
+        assert_eq!(8080, config.current().config().port);
+        assert_eq!(80, previous.config().port);
+        assert!(matches!(events.try_recv(), Ok(ReloadEvent::Changed(changes)) if changes == diff));
+    }
+
+    #[test]
+    fn failed_reload_keeps_current() {
+        let path = write_file("failed.conf", "[server]\nport = 80\nname = web\n");
+        let config = reloadable(&path);
+        let events = config.subscribe();
+
+        fs::write(&path, "[server]\nport = eighty\nname = web\n").unwrap();
+        assert!(config.reload().is_err());
+        assert!(matches!(events.try_recv(), Ok(ReloadEvent::Failed(_))));
+
+        fs::write(&path, "[server\n").unwrap();
+        assert!(matches!(config.reload(), Err(ConfigError::Source { .. })));
+        assert_eq!(80, config.current().config().port);
+    }
+
+    #[test]
+    fn watch() {
+        let path = write_file("watch.conf", "[server]\nport = 80\nname = web\n");
+        let config = Arc::new(reloadable(&path));
+        let events = config.subscribe();
+        let watcher = config.watch(Duration::from_millis(20));
+
+        std::thread::sleep(Duration::from_millis(50));
+        fs::write(&path, "[server]\nport = 80\nname = api server\n").unwrap();
+
+        // The watcher may see the file while it is being written, wait for the complete one.
+        loop {
+            match events.recv_timeout(Duration::from_secs(5)).unwrap() {
+                ReloadEvent::Changed(diff) if diff.get("server.name").is_some() => break,
+                _ => continue,
+            }
+        }
+        assert_eq!("api server", config.current().config().name);
+
+        drop(watcher);
+    }
+}
@@ -0,0 +1,536 @@
+use std::{fmt::Display, path::PathBuf};
+
+use crate::{
+    pool::{AliasedFlatPool, FlatPool, SimpleFlatPool},
+    ConfigBuilder, ConfigError, Constraint, FlatValue, Validator,
+};
+
+/// ## Setting types
+///
+/// Expected type of a setting value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingType {
+    Integer,
+    Text,
+    Boolean,
+}
+
+impl SettingType {
+    fn matches(&self, value: &FlatValue) -> bool {
+        matches!(
+            (self, value),
+            (Self::Integer, FlatValue::Integer(_))
+                | (Self::Text, FlatValue::Text(_))
+                | (Self::Boolean, FlatValue::Boolean(_))
+        )
+    }
+}
+
+impl Display for SettingType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Integer => "integer",
+            Self::Text => "text",
+            Self::Boolean => "boolean",
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+/// ## Setting schema
+///
+/// Description of a setting: its key, expected type, default value, documentation and the
+/// constraints its value must satisfy. A required setting must be present in the pool unless it
+/// has a default value. The range applies to integer values, `allowed` lists the only values
+/// accepted and `validators` hold the other rules. A deprecated setting is still read, the
+/// deprecation message tells what to use instead. Aliases are former names of the setting.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SettingSchema {
+    pub key: String,
+    pub setting_type: SettingType,
+    pub default: Option<FlatValue>,
+    pub description: Option<String>,
+    pub required: bool,
+    pub min: Option<isize>,
+    pub max: Option<isize>,
+    pub allowed: Vec<FlatValue>,
+    pub validators: Vec<Validator>,
+    pub deprecated: Option<String>,
+    pub aliases: Vec<String>,
+}
+
+impl SettingSchema {
+    pub fn new(key: &str, setting_type: SettingType) -> Self {
+        Self {
+            key: key.to_string(),
+            setting_type,
+            default: None,
+            description: None,
+            required: false,
+            min: None,
+            max: None,
+            allowed: Vec::new(),
+            validators: Vec::new(),
+            deprecated: None,
+            aliases: Vec::new(),
+        }
+    }
+
+    /// Value used when the setting is absent from the pool.
+    pub fn default_value(mut self, value: FlatValue) -> Self {
+        self.default = Some(value);
+
+        self
+    }
+
+    /// Documentation of the setting.
+    pub fn description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+
+        self
+    }
+
+    /// The setting must be present in the pool or have a default value.
+    pub fn required(mut self) -> Self {
+        self.required = true;
+
+        self
+    }
+
+    /// Inclusive bounds of an integer setting.
+    pub fn range(self, min: isize, max: isize) -> Self {
+        self.min(min).max(max)
+    }
+
+    /// Lowest value of an integer setting.
+    pub fn min(mut self, min: isize) -> Self {
+        self.min = Some(min);
+
+        self
+    }
+
+    /// Highest value of an integer setting.
+    pub fn max(mut self, max: isize) -> Self {
+        self.max = Some(max);
+
+        self
+    }
+
+    /// Values accepted for the setting.
+    pub fn allowed(mut self, values: Vec<FlatValue>) -> Self {
+        self.allowed = values;
+
+        self
+    }
+
+    /// Add a rule the value must satisfy.
+    pub fn validator(mut self, validator: Validator) -> Self {
+        self.validators.push(validator);
+
+        self
+    }
+
+    /// Mark the setting as deprecated, the message tells what to use instead.
+    pub fn deprecated(mut self, message: &str) -> Self {
+        self.deprecated = Some(message.to_string());
+
+        self
+    }
+
+    /// Former name of the setting, still read but reported as deprecated.
+    pub fn alias(mut self, alias: &str) -> Self {
+        self.aliases.push(alias.to_string());
+
+        self
+    }
+
+    /// Check the value of this setting in the given pool.
+    fn validate(&self, pool: &(impl FlatPool + ?Sized)) -> Result<(), ConfigError> {
+        if !pool.has(&self.key) {
+            return match self.required {
+                true => Err(ConfigError::Missing {
+                    field_name: self.key.clone(),
+                    fields: format!("{pool:?}"),
+                }),
+                false => Ok(()),
+            };
+        }
+
+        let value = pool.require(&self.key)?;
+
+        if !self.setting_type.matches(&value) {
+            return Err(ConfigError::TypeMismatch {
+                expected: self.setting_type.to_string(),
+                present: value.display(),
+            }
+            .with_key(&self.key));
+        }
+
+        let range = (self.min.is_some() || self.max.is_some()).then_some(Validator::Range {
+            min: self.min,
+            max: self.max,
+        });
+        let allowed = (!self.allowed.is_empty()).then(|| Validator::OneOf(self.allowed.clone()));
+
+        for validator in range.iter().chain(&allowed).chain(&self.validators) {
+            validator.check(pool, &self.key)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// ## Schema
+///
+/// Single definition of the settings of an application and the constraints between them. The
+/// schema validates a pool reporting all the invalid settings at once, provides the default
+/// values and drives the configuration builder.
+///
+/// ```rust
+/// use flat_config::{ConfigBuilder, ConfigError, Schema, SettingSchema, SettingType, TryUnwrap,
+///     pool::{FlatPool, SimpleFlatPool}};
+///
+/// struct PortBuilder;
+///
+/// impl ConfigBuilder<isize> for PortBuilder {
+///     fn build(&self, config_pool: &impl FlatPool) -> Result<isize, ConfigError> {
+///         config_pool.require("port")?.try_unwrap()
+///     }
+/// }
+///
+/// let mut schema = Schema::default();
+/// schema.add(
+///     SettingSchema::new("port", SettingType::Integer)
+///         .description("Port the server listens on.")
+///         .default_value(8080.into())
+///         .range(1, 65535),
+/// );
+///
+/// assert_eq!(8080, schema.build(&SimpleFlatPool::default(), &PortBuilder).unwrap());
+///
+/// let mut pool = SimpleFlatPool::default();
+/// pool.add("port", 0.into());
+/// assert!(schema.validate(&pool).is_err());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Schema {
+    settings: Vec<SettingSchema>,
+    constraints: Vec<Constraint>,
+}
+
+impl Schema {
+    /// Add a setting to the schema, a setting with the same key is replaced.
+    pub fn add(&mut self, setting: SettingSchema) -> &mut Self {
+        match self.settings.iter_mut().find(|s| s.key == setting.key) {
+            Some(existing) => *existing = setting,
+            None => self.settings.push(setting),
+        }
+
+        self
+    }
+
+    /// Add a constraint spanning several settings.
+    pub fn constrain(&mut self, constraint: Constraint) -> &mut Self {
+        self.constraints.push(constraint);
+
+        self
+    }
+
+    /// Constraints in the order they were added.
+    pub fn constraints(&self) -> &[Constraint] {
+        &self.constraints
+    }
+
+    /// Schema of the given setting.
+    pub fn get(&self, key: &str) -> Option<&SettingSchema> {
+        self.settings.iter().find(|setting| setting.key == key)
+    }
+
+    /// Settings in the order they were added.
+    pub fn settings(&self) -> &[SettingSchema] {
+        &self.settings
+    }
+
+    /// Pool holding the default values, it can be used as the bottom layer of a
+    /// [LayeredFlatPool](crate::pool::LayeredFlatPool).
+    pub fn defaults(&self) -> SimpleFlatPool {
+        let mut pool = SimpleFlatPool::default();
+
+        for setting in &self.settings {
+            if let Some(value) = &setting.default {
+                pool.add(&setting.key, value.clone());
+            }
+        }
+
+        pool
+    }
+
+    /// Wrap the given pool so the settings are also read under their aliases, deprecated settings
+    /// and aliases are reported by the returned pool.
+    pub fn aliased<P: FlatPool>(&self, pool: P) -> AliasedFlatPool<P> {
+        let mut pool = AliasedFlatPool::new(pool);
+
+        for setting in &self.settings {
+            for alias in &setting.aliases {
+                pool.alias(alias, &setting.key);
+            }
+            if let Some(message) = &setting.deprecated {
+                pool.deprecate(&setting.key, message);
+            }
+        }
+
+        pool
+    }
+
+    /// Check every setting of the schema against the given pool, default values included, then
+    /// the constraints between settings. All the invalid settings and violated constraints are
+    /// reported in a [ConfigError::Invalid] error.
+    pub fn validate(&self, pool: &(impl FlatPool + ?Sized)) -> Result<(), ConfigError> {
+        let pool = DefaultedPool {
+            pool,
+            defaults: self.defaults(),
+        };
+        let errors: Vec<ConfigError> = self
+            .settings
+            .iter()
+            .filter_map(|setting| setting.validate(&pool).err())
+            .chain(
+                self.constraints
+                    .iter()
+                    .filter_map(|constraint| constraint.check(&pool).err()),
+            )
+            .collect();
+
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(ConfigError::Invalid(errors)),
+        }
+    }
+
+    /// Validate the given pool, then build the configuration from it with the default values
+    /// applied.
+    pub fn build<T>(
+        &self,
+        pool: &(impl FlatPool + ?Sized),
+        builder: &impl ConfigBuilder<T>,
+    ) -> Result<T, ConfigError> {
+        self.validate(pool)?;
+
+        builder.build(&DefaultedPool {
+            pool,
+            defaults: self.defaults(),
+        })
+    }
+}
+
+/// Pool falling back to the default values of a schema.
+#[derive(Debug)]
+struct DefaultedPool<'a, P: ?Sized> {
+    pool: &'a P,
+    defaults: SimpleFlatPool,
+}
+
+impl<P: FlatPool + ?Sized> FlatPool for DefaultedPool<'_, P> {
+    fn get(&self, name: &str) -> Option<FlatValue> {
+        self.pool.get(name).or_else(|| self.defaults.get(name))
+    }
+
+    fn get_borrow(&self, name: &str) -> Option<&FlatValue> {
+        match self.pool.has(name) {
+            true => self.pool.get_borrow(name),
+            false => self.defaults.get_borrow(name),
+        }
+    }
+
+    fn require(&self, name: &str) -> Result<FlatValue, ConfigError> {
+        match self.pool.has(name) {
+            true => self.pool.require(name),
+            false => self
+                .defaults
+                .require(name)
+                .map_err(|_| ConfigError::Missing {
+                    field_name: name.to_string(),
+                    fields: format!("{:?}", self.pool),
+                }),
+        }
+    }
+
+    fn unwrap(&self, name: &str) -> FlatValue {
+        self.require(name)
+            .map_err(|e| panic!("This should never happen: {e}"))
+            .unwrap()
+    }
+
+    fn get_or(&self, name: &str, default: FlatValue) -> FlatValue {
+        self.get(name).unwrap_or(default)
+    }
+
+    fn has(&self, name: &str) -> bool {
+        self.pool.has(name) || self.defaults.has(name)
+    }
+
+    fn keys(&self) -> Vec<String> {
+        let mut keys = self.pool.keys();
+        keys.extend(self.defaults.keys());
+        keys.sort();
+        keys.dedup();
+
+        keys
+    }
+
+    fn is_secret(&self, name: &str) -> bool {
+        self.pool.is_secret(name)
+    }
+
+    fn source_files(&self) -> Vec<PathBuf> {
+        self.pool.source_files()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TryUnwrap;
+
+    fn init_schema() -> Schema {
+        let mut schema = Schema::default();
+        schema
+            .add(
+                SettingSchema::new("server.port", SettingType::Integer)
+                    .description("Port the server listens on.")
+                    .default_value(8080.into())
+                    .range(1, 65535),
+            )
+            .add(
+                SettingSchema::new("server.name", SettingType::Text)
+                    .required()
+                    .validator(Validator::not_empty()),
+            )
+            .add(
+                SettingSchema::new("log.level", SettingType::Text)
+                    .allowed(vec!["debug".into(), "info".into(), "error".into()])
+                    .default_value("info".into()),
+            )
+            .add(SettingSchema::new("workers", SettingType::Integer).min(1))
+            .add(SettingSchema::new("dry_run", SettingType::Boolean).deprecated("use 'mode'"))
+            .constrain(Constraint::conflicts_with("dry_run", &["workers"]));
+
+        schema
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct ServerConfig {
+        port: isize,
+        name: String,
+        level: String,
+    }
+
+    struct ServerConfigBuilder;
+
+    impl ConfigBuilder<ServerConfig> for ServerConfigBuilder {
+        fn build(&self, config_pool: &impl FlatPool) -> Result<ServerConfig, ConfigError> {
+            Ok(ServerConfig {
+                port: config_pool.require("server.port")?.try_unwrap()?,
+                name: config_pool.require("server.name")?.try_unwrap()?,
+                level: config_pool.require("log.level")?.try_unwrap()?,
+            })
+        }
+    }
+
+    #[test]
+    fn schema() {
+        let mut schema = init_schema();
+        schema.add(SettingSchema::new("workers", SettingType::Integer).min(2));
+
+        assert_eq!(5, schema.settings().len());
+        assert_eq!(Some(2), schema.get("workers").unwrap().min);
+        assert_eq!(vec!["log.level", "server.port"], schema.defaults().keys());
+    }
+
+    #[test]
+    fn build() {
+        let mut pool = SimpleFlatPool::default();
+        pool.add("server.name", "web".into());
+
+        assert_eq!(
+            ServerConfig {
+                port: 8080,
+                name: "web".to_string(),
+                level: "info".to_string(),
+            },
+            init_schema().build(&pool, &ServerConfigBuilder).unwrap()
+        );
+    }
+
+    #[test]
+    fn validate() {
+        let mut pool = SimpleFlatPool::default();
+        pool.add("server.port", 0.into())
+            .add("server.name", " ".into())
+            .add("log.level", "verbose".into())
+            .add("workers", 0.into())
+            .add("dry_run", "yes".into());
+
+        let error = init_schema().validate(&pool).unwrap_err();
+        let ConfigError::Invalid(errors) = &error else {
+            panic!("unexpected error {error:?}");
+        };
+        let message = error.to_string();
+
+        assert_eq!(6, errors.len());
+        assert!(message
+            .contains("Setting 'server.port': Value 0 does not satisfy the rule range(1, 65535)."));
+        assert!(message
+            .contains("Setting 'server.name': Value \" \" does not satisfy the rule not_empty."));
+        assert!(message.contains(
+            "Value \"verbose\" does not satisfy the rule one_of(\"debug\", \"info\", \"error\")."
+        ));
+        assert!(message.contains("Setting 'workers': Value 0 does not satisfy the rule min(1)."));
+        assert!(
+            message.contains("Setting 'dry_run': Type mismatch, expected 'boolean' got 'text'.")
+        );
+        assert!(
+            message.contains("Settings 'dry_run', 'workers': 'dry_run' conflicts with 'workers'.")
+        );
+    }
+
+    #[test]
+    fn aliased() {
+        let mut schema = init_schema();
+        schema.add(
+            SettingSchema::new("server.name", SettingType::Text)
+                .required()
+                .alias("name"),
+        );
+        let mut pool = SimpleFlatPool::default();
+        pool.add("name", "web".into()).add("dry_run", true.into());
+        let pool = schema.aliased(pool);
+
+        assert_eq!(
+            "web",
+            schema.build(&pool, &ServerConfigBuilder).unwrap().name
+        );
+        assert_eq!(
+            vec![
+                "'dry_run' is deprecated: use 'mode'",
+                "'name' is deprecated, use 'server.name' instead"
+            ],
+            pool.deprecations()
+                .iter()
+                .map(|deprecation| deprecation.to_string())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn secrets() {
+        let mut schema = Schema::default();
+        schema.add(SettingSchema::new("pin", SettingType::Integer).range(1000, 9999));
+        let mut pool = SimpleFlatPool::default();
+        pool.add_secret("pin", 12345.into());
+
+        let message = schema.validate(&pool).unwrap_err().to_string();
+        assert!(!message.contains("12345"), "{message}");
+    }
+}
@@ -0,0 +1,190 @@
+use std::fmt::{Debug, Display};
+
+use crate::{ConfigError, FlatValue, TryUnwrap};
+
+/// Text shown in place of secret values.
+pub const REDACTED: &str = "***";
+
+/// ## Secret string
+///
+/// A text whose content never shows in `Debug` or `Display` output. Comparisons take the same
+/// time whatever the position of the first difference. With the `zeroize` feature, the content is
+/// overwritten with zeros when dropped, copies left behind by the text it was read from are not.
+///
+/// ```rust
+/// use flat_config::{FlatValue, SecretString, TryUnwrap};
+///
+/// let password: SecretString = FlatValue::from("hunter2").try_unwrap().unwrap();
+///
+/// assert_eq!("hunter2", password.expose());
+/// assert_eq!("***", format!("{password}"));
+/// ```
+#[derive(Clone)]
+pub struct SecretString(String);
+
+impl SecretString {
+    pub fn new(secret: String) -> Self {
+        Self(secret)
+    }
+
+    /// Access the secret content.
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl PartialEq for SecretString {
+    fn eq(&self, other: &Self) -> bool {
+        let (a, b) = (self.0.as_bytes(), other.0.as_bytes());
+
+        a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+    }
+}
+
+impl Eq for SecretString {}
+
+#[cfg(feature = "zeroize")]
+impl Drop for SecretString {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(&mut self.0);
+    }
+}
+
+impl Debug for SecretString {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SecretString({REDACTED})")
+    }
+}
+
+impl Display for SecretString {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{REDACTED}")
+    }
+}
+
+impl From<&str> for SecretString {
+    fn from(value: &str) -> Self {
+        Self(value.to_string())
+    }
+}
+
+impl TryUnwrap<SecretString> for FlatValue {
+    fn try_unwrap(&self) -> Result<SecretString, ConfigError> {
+        match self {
+            Self::Text(t) => Ok(SecretString::new(t.to_string())),
+            _ => Err(ConfigError::TypeMismatch {
+                expected: "text".to_string(),
+                present: self.display(),
+            }),
+        }
+    }
+}
+
+/// ## Secret keys
+///
+/// Set of key patterns designating secret settings. Patterns may contain `*` wildcards
+/// (`*password*`) and are matched case insensitively.
+#[derive(Debug, Clone, Default)]
+pub struct SecretKeys {
+    patterns: Vec<String>,
+    all: bool,
+}
+
+impl SecretKeys {
+    /// Mark the keys matching the given pattern as secret.
+    pub fn add(&mut self, pattern: &str) -> &mut Self {
+        self.patterns.push(pattern.to_ascii_lowercase());
+
+        self
+    }
+
+    /// Mark every key as secret.
+    pub fn add_all(&mut self) -> &mut Self {
+        self.all = true;
+
+        self
+    }
+
+    /// Is the given key secret?
+    pub fn matches(&self, key: &str) -> bool {
+        let key = key.to_ascii_lowercase();
+
+        self.all
+            || self
+                .patterns
+                .iter()
+                .any(|pattern| wildcard_match(pattern, &key))
+    }
+}
+
+/// Match a text against a pattern where `*` stands for any sequence of characters.
+pub(crate) fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+
+    for part in middle {
+        match rest.find(part) {
+            Some(position) => rest = &rest[position + part.len()..],
+            None => return false,
+        }
+    }
+
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn secret_string() {
+        let secret: SecretString = FlatValue::from("hunter2").try_unwrap().unwrap();
+
+        assert_eq!("hunter2", secret.expose());
+        assert_eq!("***", secret.to_string());
+        assert_eq!("SecretString(***)", format!("{secret:?}"));
+
+        let result: Result<SecretString, ConfigError> = FlatValue::Integer(1).try_unwrap();
+        assert!(result.is_err());
+
+        assert_eq!(SecretString::from("hunter2"), secret);
+        assert_ne!(SecretString::from("hunter3"), secret);
+        assert_ne!(SecretString::from("hunter"), secret);
+    }
+
+    #[test]
+    fn wildcards() {
+        assert!(wildcard_match("*password*", "database.password"));
+        assert!(wildcard_match("*password*", "password_file"));
+        assert!(wildcard_match("api.*", "api.token"));
+        assert!(wildcard_match("*.key", "tls.key"));
+        assert!(wildcard_match("a*b*c", "a_b_c"));
+        assert!(wildcard_match("token", "token"));
+        assert!(!wildcard_match("token", "tokens"));
+        assert!(!wildcard_match("*.key", "tls.keys"));
+        assert!(!wildcard_match("a*b*c", "a_c_b"));
+        assert!(!wildcard_match("ab*ba", "aba"));
+    }
+
+    #[test]
+    fn secret_keys() {
+        let mut keys = SecretKeys::default();
+        keys.add("*PASSWORD*").add("api.token");
+
+        assert!(keys.matches("database.password"));
+        assert!(keys.matches("API.Token"));
+        assert!(!keys.matches("database.host"));
+
+        keys.add_all();
+        assert!(keys.matches("database.host"));
+    }
+}
@@ -0,0 +1,658 @@
+use std::fmt::Display;
+
+use serde::ser::{self, Impossible, Serialize};
+
+use crate::{pool::SimpleFlatPool, ConfigError, FlatValue};
+
+impl ser::Error for ConfigError {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self::IncorrectValue(msg.to_string())
+    }
+}
+
+fn unsupported(what: &str, path: &str) -> ConfigError {
+    ConfigError::IncorrectValue(format!("{what} cannot be stored in a flat pool")).with_key(path)
+}
+
+fn join(path: &str, segment: &str) -> String {
+    if path.is_empty() {
+        segment.to_string()
+    } else {
+        format!("{path}.{segment}")
+    }
+}
+
+/// ## Pool serializer
+///
+/// Serde serializer flattening a data structure into a [SimpleFlatPool], this is the reverse of
+/// the [PoolDeserializer](crate::PoolDeserializer). Nested structures and maps become dotted keys,
+/// sequences become indexed keys (`servers.0`, `servers.1`…), `None` values are left out and unit
+/// enum variants are stored as texts. Floating point numbers are not supported.
+///
+/// ```rust
+/// use flat_config::{to_pool, pool::FlatPool, FlatValue};
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct Database {
+///     host: String,
+///     port: u16,
+/// }
+///
+/// #[derive(Serialize)]
+/// struct AppConfig {
+///     database: Database,
+///     dry_run: Option<bool>,
+/// }
+///
+/// let defaults = AppConfig {
+///     database: Database { host: "localhost".to_string(), port: 5432 },
+///     dry_run: None,
+/// };
+/// let pool = to_pool(&defaults).unwrap();
+///
+/// assert_eq!(Some(FlatValue::Integer(5432)), pool.get("database.port"));
+/// assert!(!pool.has("dry_run"));
+/// ```
+#[derive(Debug)]
+pub struct PoolSerializer<'a> {
+    pool: &'a mut SimpleFlatPool,
+    path: String,
+}
+
+impl<'a> PoolSerializer<'a> {
+    /// Values are added to the given pool, replacing the ones with the same key.
+    pub fn new(pool: &'a mut SimpleFlatPool) -> Self {
+        Self {
+            pool,
+            path: String::new(),
+        }
+    }
+
+    fn store(self, value: FlatValue) -> Result<(), ConfigError> {
+        if self.path.is_empty() {
+            return Err(ConfigError::IncorrectValue(
+                "only structures and maps can be turned into a flat pool".to_string(),
+            ));
+        }
+
+        self.pool.add(&self.path, value);
+
+        Ok(())
+    }
+
+    fn store_integer<T: TryInto<isize> + Display + Copy>(
+        self,
+        value: T,
+    ) -> Result<(), ConfigError> {
+        let integer = value.try_into().map_err(|_| {
+            ConfigError::IncorrectValue(format!("integer {value} is too large"))
+                .with_key(&self.path)
+        })?;
+
+        self.store(FlatValue::Integer(integer))
+    }
+
+    fn nested(self) -> NestedSerializer<'a> {
+        NestedSerializer {
+            pool: self.pool,
+            path: self.path,
+            index: 0,
+            key: None,
+        }
+    }
+}
+
+impl<'a> ser::Serializer for PoolSerializer<'a> {
+    type Ok = ();
+    type Error = ConfigError;
+    type SerializeSeq = NestedSerializer<'a>;
+    type SerializeTuple = NestedSerializer<'a>;
+    type SerializeTupleStruct = NestedSerializer<'a>;
+    type SerializeTupleVariant = Impossible<(), ConfigError>;
+    type SerializeMap = NestedSerializer<'a>;
+    type SerializeStruct = NestedSerializer<'a>;
+    type SerializeStructVariant = Impossible<(), ConfigError>;
+
+    fn serialize_bool(self, v: bool) -> Result<(), ConfigError> {
+        self.store(FlatValue::Boolean(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), ConfigError> {
+        self.store_integer(v)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<(), ConfigError> {
+        self.store_integer(v)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<(), ConfigError> {
+        self.store_integer(v)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<(), ConfigError> {
+        self.store_integer(v)
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<(), ConfigError> {
+        self.store_integer(v)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), ConfigError> {
+        self.store_integer(v)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<(), ConfigError> {
+        self.store_integer(v)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<(), ConfigError> {
+        self.store_integer(v)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<(), ConfigError> {
+        self.store_integer(v)
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<(), ConfigError> {
+        self.store_integer(v)
+    }
+
+    fn serialize_f32(self, _v: f32) -> Result<(), ConfigError> {
+        Err(unsupported("floating point number", &self.path))
+    }
+
+    fn serialize_f64(self, _v: f64) -> Result<(), ConfigError> {
+        Err(unsupported("floating point number", &self.path))
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), ConfigError> {
+        self.store(FlatValue::Text(v.to_string()))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), ConfigError> {
+        self.store(FlatValue::Text(v.to_string()))
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<(), ConfigError> {
+        Err(unsupported("byte array", &self.path))
+    }
+
+    fn serialize_none(self) -> Result<(), ConfigError> {
+        Ok(())
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<(), ConfigError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), ConfigError> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), ConfigError> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<(), ConfigError> {
+        self.store(FlatValue::Text(variant.to_string()))
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), ConfigError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<(), ConfigError> {
+        Err(unsupported("enum variant with data", &self.path))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<NestedSerializer<'a>, ConfigError> {
+        Ok(self.nested())
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<NestedSerializer<'a>, ConfigError> {
+        Ok(self.nested())
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<NestedSerializer<'a>, ConfigError> {
+        Ok(self.nested())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, ConfigError> {
+        Err(unsupported("enum variant with data", &self.path))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<NestedSerializer<'a>, ConfigError> {
+        Ok(self.nested())
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<NestedSerializer<'a>, ConfigError> {
+        Ok(self.nested())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, ConfigError> {
+        Err(unsupported("enum variant with data", &self.path))
+    }
+}
+
+/// Serializer of the structures, maps and sequences met by the [PoolSerializer], each element is
+/// stored under its own key.
+pub struct NestedSerializer<'a> {
+    pool: &'a mut SimpleFlatPool,
+    path: String,
+    index: usize,
+    key: Option<String>,
+}
+
+impl NestedSerializer<'_> {
+    fn element<T: ?Sized + Serialize>(
+        &mut self,
+        segment: &str,
+        value: &T,
+    ) -> Result<(), ConfigError> {
+        value.serialize(PoolSerializer {
+            pool: self.pool,
+            path: join(&self.path, segment),
+        })
+    }
+
+    fn next_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), ConfigError> {
+        let index = self.index.to_string();
+        self.index += 1;
+
+        self.element(&index, value)
+    }
+}
+
+impl ser::SerializeSeq for NestedSerializer<'_> {
+    type Ok = ();
+    type Error = ConfigError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), ConfigError> {
+        self.next_element(value)
+    }
+
+    fn end(self) -> Result<(), ConfigError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTuple for NestedSerializer<'_> {
+    type Ok = ();
+    type Error = ConfigError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), ConfigError> {
+        self.next_element(value)
+    }
+
+    fn end(self) -> Result<(), ConfigError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleStruct for NestedSerializer<'_> {
+    type Ok = ();
+    type Error = ConfigError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), ConfigError> {
+        self.next_element(value)
+    }
+
+    fn end(self) -> Result<(), ConfigError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeMap for NestedSerializer<'_> {
+    type Ok = ();
+    type Error = ConfigError;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), ConfigError> {
+        self.key = Some(key.serialize(KeySerializer)?);
+
+        Ok(())
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), ConfigError> {
+        let key = self.key.take().ok_or_else(|| {
+            ConfigError::IncorrectValue("map value serialized before its key".to_string())
+        })?;
+
+        self.element(&key, value)
+    }
+
+    fn end(self) -> Result<(), ConfigError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStruct for NestedSerializer<'_> {
+    type Ok = ();
+    type Error = ConfigError;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), ConfigError> {
+        self.element(key, value)
+    }
+
+    fn end(self) -> Result<(), ConfigError> {
+        Ok(())
+    }
+}
+
+/// Turn map keys into key segments, only texts and integers are accepted.
+struct KeySerializer;
+
+impl KeySerializer {
+    fn invalid() -> ConfigError {
+        ConfigError::IncorrectValue("map keys must be texts or integers".to_string())
+    }
+}
+
+impl ser::Serializer for KeySerializer {
+    type Ok = String;
+    type Error = ConfigError;
+    type SerializeSeq = Impossible<String, ConfigError>;
+    type SerializeTuple = Impossible<String, ConfigError>;
+    type SerializeTupleStruct = Impossible<String, ConfigError>;
+    type SerializeTupleVariant = Impossible<String, ConfigError>;
+    type SerializeMap = Impossible<String, ConfigError>;
+    type SerializeStruct = Impossible<String, ConfigError>;
+    type SerializeStructVariant = Impossible<String, ConfigError>;
+
+    fn serialize_bool(self, _v: bool) -> Result<String, ConfigError> {
+        Err(Self::invalid())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<String, ConfigError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<String, ConfigError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<String, ConfigError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<String, ConfigError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<String, ConfigError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<String, ConfigError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<String, ConfigError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<String, ConfigError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_f32(self, _v: f32) -> Result<String, ConfigError> {
+        Err(Self::invalid())
+    }
+
+    fn serialize_f64(self, _v: f64) -> Result<String, ConfigError> {
+        Err(Self::invalid())
+    }
+
+    fn serialize_char(self, v: char) -> Result<String, ConfigError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<String, ConfigError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<String, ConfigError> {
+        Err(Self::invalid())
+    }
+
+    fn serialize_none(self) -> Result<String, ConfigError> {
+        Err(Self::invalid())
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<String, ConfigError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<String, ConfigError> {
+        Err(Self::invalid())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<String, ConfigError> {
+        Err(Self::invalid())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<String, ConfigError> {
+        Ok(variant.to_string())
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<String, ConfigError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<String, ConfigError> {
+        Err(Self::invalid())
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, ConfigError> {
+        Err(Self::invalid())
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, ConfigError> {
+        Err(Self::invalid())
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, ConfigError> {
+        Err(Self::invalid())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, ConfigError> {
+        Err(Self::invalid())
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, ConfigError> {
+        Err(Self::invalid())
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, ConfigError> {
+        Err(Self::invalid())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, ConfigError> {
+        Err(Self::invalid())
+    }
+}
+
+/// Flatten the given data structure into a new [SimpleFlatPool].
+pub fn to_pool<T: ?Sized + Serialize>(value: &T) -> Result<SimpleFlatPool, ConfigError> {
+    let mut pool = SimpleFlatPool::default();
+    value.serialize(PoolSerializer::new(&mut pool))?;
+
+    Ok(pool)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::{
+        from_pool,
+        pool::{FlatPool, LayeredFlatPool},
+    };
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    enum Mode {
+        Fast,
+        Safe,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Database {
+        host: String,
+        port: u16,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct AppConfig {
+        database: Database,
+        dry_run: Option<bool>,
+        mode: Mode,
+        servers: Vec<String>,
+        limits: BTreeMap<String, u32>,
+    }
+
+    fn defaults() -> AppConfig {
+        AppConfig {
+            database: Database {
+                host: "localhost".to_string(),
+                port: 5432,
+            },
+            dry_run: None,
+            mode: Mode::Safe,
+            servers: vec!["a".to_string(), "b".to_string()],
+            limits: BTreeMap::from([("cpu".to_string(), 4)]),
+        }
+    }
+
+    #[test]
+    fn flatten() {
+        let pool = to_pool(&defaults()).unwrap();
+
+        assert_eq!(
+            vec![
+                "database.host",
+                "database.port",
+                "limits.cpu",
+                "mode",
+                "servers.0",
+                "servers.1"
+            ],
+            pool.keys()
+        );
+        assert_eq!(Some(FlatValue::Integer(5432)), pool.get("database.port"));
+        assert_eq!(Some(FlatValue::from("Safe")), pool.get("mode"));
+        assert_eq!(Some(FlatValue::from("b")), pool.get("servers.1"));
+    }
+
+    #[test]
+    fn round_trip() {
+        let pool = to_pool(&defaults()).unwrap();
+        let config: AppConfig = from_pool(&pool).unwrap();
+
+        assert_eq!(defaults(), config);
+    }
+
+    #[test]
+    fn default_layer() {
+        let mut file_pool = SimpleFlatPool::default();
+        file_pool.add("database.port", 6432.into());
+        let pool = LayeredFlatPool::new(vec![
+            Box::new(to_pool(&defaults()).unwrap()),
+            Box::new(file_pool),
+        ]);
+        let config: AppConfig = from_pool(&pool).unwrap();
+
+        assert_eq!(6432, config.database.port);
+        assert_eq!("localhost", config.database.host);
+    }
+
+    #[test]
+    fn unsupported_values() {
+        #[derive(Serialize)]
+        struct WithFloat {
+            ratio: f64,
+        }
+
+        let error = to_pool(&WithFloat { ratio: 0.5 }).unwrap_err();
+        assert!(matches!(error, ConfigError::Setting { ref key, .. } if key == "ratio"));
+
+        assert!(to_pool(&12).is_err());
+        assert!(to_pool(&BTreeMap::from([("big", u64::MAX)])).is_err());
+    }
+}
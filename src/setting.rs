@@ -0,0 +1,504 @@
+use std::{
+    mem::ManuallyDrop,
+    net::{IpAddr, SocketAddr},
+    path::PathBuf,
+    time::Duration,
+};
+
+use crate::{
+    duration::format_duration, pool::FlatPool, ByteSize, ConfigError, FlatEnum, FlatValue,
+    HostPort, Schema, SecretString, SettingSchema, SettingType, TryUnwrap,
+};
+
+/// ## Setting values
+///
+/// Rust types a [Setting] can be bound to: they are read from flat values with [TryUnwrap] and
+/// written back as flat values to document their defaults.
+pub trait SettingValue: Sized + Clone {
+    /// Type of the flat values holding this type.
+    const SETTING_TYPE: SettingType;
+
+    fn from_flat(value: &FlatValue) -> Result<Self, ConfigError>;
+
+    /// Flat value holding this value. Secret types give their plain value, the caller must store
+    /// it with [add_secret](crate::pool::SimpleFlatPool::add_secret) to keep it redacted.
+    fn to_flat(&self) -> FlatValue;
+}
+
+macro_rules! integer_setting_value {
+    ($($integer:ty),*) => {
+        $(
+            impl SettingValue for $integer {
+                const SETTING_TYPE: SettingType = SettingType::Integer;
+
+                fn from_flat(value: &FlatValue) -> Result<Self, ConfigError> {
+                    value.try_unwrap()
+                }
+
+                /// Values beyond the range of flat integers are saturated.
+                fn to_flat(&self) -> FlatValue {
+                    FlatValue::Integer(isize::try_from(*self).unwrap_or(isize::MAX))
+                }
+            }
+        )*
+    };
+}
+
+integer_setting_value!(isize, i8, i16, i32, i64, u8, u16, u32, u64, usize);
+
+/// Types written as texts, using their `Display` implementation.
+macro_rules! text_setting_value {
+    ($($text:ty),*) => {
+        $(
+            impl SettingValue for $text {
+                const SETTING_TYPE: SettingType = SettingType::Text;
+
+                fn from_flat(value: &FlatValue) -> Result<Self, ConfigError> {
+                    value.try_unwrap()
+                }
+
+                fn to_flat(&self) -> FlatValue {
+                    FlatValue::Text(self.to_string())
+                }
+            }
+        )*
+    };
+}
+
+text_setting_value!(String, IpAddr, SocketAddr, HostPort);
+
+impl SettingValue for ByteSize {
+    const SETTING_TYPE: SettingType = SettingType::Text;
+
+    fn from_flat(value: &FlatValue) -> Result<Self, ConfigError> {
+        value.try_unwrap()
+    }
+
+    /// Written in the exact form, the rounded display would not read back the same size.
+    fn to_flat(&self) -> FlatValue {
+        FlatValue::from(*self)
+    }
+}
+
+impl SettingValue for bool {
+    const SETTING_TYPE: SettingType = SettingType::Boolean;
+
+    fn from_flat(value: &FlatValue) -> Result<Self, ConfigError> {
+        value.try_unwrap()
+    }
+
+    fn to_flat(&self) -> FlatValue {
+        FlatValue::Boolean(*self)
+    }
+}
+
+impl SettingValue for PathBuf {
+    const SETTING_TYPE: SettingType = SettingType::Text;
+
+    fn from_flat(value: &FlatValue) -> Result<Self, ConfigError> {
+        value.try_unwrap()
+    }
+
+    fn to_flat(&self) -> FlatValue {
+        FlatValue::Text(self.display().to_string())
+    }
+}
+
+impl SettingValue for Duration {
+    const SETTING_TYPE: SettingType = SettingType::Text;
+
+    fn from_flat(value: &FlatValue) -> Result<Self, ConfigError> {
+        value.try_unwrap()
+    }
+
+    fn to_flat(&self) -> FlatValue {
+        FlatValue::Text(format_duration(self))
+    }
+}
+
+/// The secret is exposed: `to_flat` gives a plain text which is not redacted by itself, store it
+/// in a pool with [add_secret](crate::pool::SimpleFlatPool::add_secret).
+impl SettingValue for SecretString {
+    const SETTING_TYPE: SettingType = SettingType::Text;
+
+    fn from_flat(value: &FlatValue) -> Result<Self, ConfigError> {
+        value.try_unwrap()
+    }
+
+    fn to_flat(&self) -> FlatValue {
+        FlatValue::Text(self.expose().to_string())
+    }
+}
+
+impl<T: FlatEnum> SettingValue for T {
+    const SETTING_TYPE: SettingType = SettingType::Text;
+
+    fn from_flat(value: &FlatValue) -> Result<Self, ConfigError> {
+        value.try_unwrap()
+    }
+
+    fn to_flat(&self) -> FlatValue {
+        FlatValue::Text(self.name().unwrap_or_default().to_string())
+    }
+}
+
+enum DefaultValue<T> {
+    // Only set for `Copy` types, which have nothing to drop, so settings can be built in const
+    // contexts whatever their type.
+    Value(ManuallyDrop<T>),
+    With(fn() -> T),
+}
+
+/// ## Typed settings
+///
+/// A setting declared once with its key, its Rust type and its default value, then read from any
+/// pool with [ReadSetting::read]. Settings are usually declared as constants, the key is checked
+/// at compile time like with the [key!](crate::key) macro. Defaults of types that are not `Copy`
+/// are given by a function.
+///
+/// ```rust
+/// use std::time::Duration;
+/// use flat_config::{ReadSetting, Setting, pool::SimpleFlatPool};
+///
+/// const PORT: Setting<u16> = Setting::new("http.port")
+///     .description("Port the server listens on.")
+///     .default(8080);
+/// const HOST: Setting<String> = Setting::new("http.host").default_with(|| "localhost".into());
+/// const TIMEOUT: Setting<Duration> = Setting::new("http.timeout");
+///
+/// let mut pool = SimpleFlatPool::default();
+/// pool.add("http.timeout", "30s".into());
+///
+/// assert_eq!(8080, pool.read(&PORT).unwrap());
+/// assert_eq!("localhost", pool.read(&HOST).unwrap());
+/// assert_eq!(Duration::from_secs(30), pool.read(&TIMEOUT).unwrap());
+/// ```
+pub struct Setting<T: 'static> {
+    key: &'static str,
+    description: Option<&'static str>,
+    default: Option<DefaultValue<T>>,
+}
+
+impl<T> Setting<T> {
+    pub const fn new(key: &'static str) -> Self {
+        assert!(crate::KeyPath::is_valid(key), "invalid setting key");
+
+        Self {
+            key,
+            description: None,
+            default: None,
+        }
+    }
+
+    pub const fn description(self, description: &'static str) -> Self {
+        Self {
+            description: Some(description),
+            ..self
+        }
+    }
+
+    /// Function building the value used when the setting is absent from the pool.
+    pub const fn default_with(self, default: fn() -> T) -> Self {
+        Self {
+            default: Some(DefaultValue::With(default)),
+            ..self
+        }
+    }
+
+    pub fn key(&self) -> &'static str {
+        self.key
+    }
+}
+
+impl<T: Copy> Setting<T> {
+    /// Value used when the setting is absent from the pool.
+    pub const fn default(self, default: T) -> Self {
+        Self {
+            default: Some(DefaultValue::Value(ManuallyDrop::new(default))),
+            ..self
+        }
+    }
+}
+
+impl<T: SettingValue> Setting<T> {
+    pub fn default_value(&self) -> Option<T> {
+        match self.default.as_ref()? {
+            DefaultValue::Value(value) => Some(T::clone(value)),
+            DefaultValue::With(default) => Some(default()),
+        }
+    }
+
+    /// Read the setting from the given pool, the default value is used when it is absent.
+    pub fn read(&self, pool: &(impl FlatPool + ?Sized)) -> Result<T, ConfigError> {
+        if !pool.has(self.key) {
+            if let Some(value) = self.default_value() {
+                return Ok(value);
+            }
+        }
+
+        T::from_flat(&pool.require(self.key)?).map_err(|e| e.with_key(self.key))
+    }
+
+    /// Schema of the setting, to document and validate it.
+    pub fn schema(&self) -> SettingSchema {
+        let mut schema = SettingSchema::new(self.key, T::SETTING_TYPE);
+
+        if let Some(description) = self.description {
+            schema = schema.description(description);
+        }
+        match self.default_value() {
+            Some(value) => schema.default_value(value.to_flat()),
+            None => schema.required(),
+        }
+    }
+}
+
+/// ## Reading typed settings
+///
+/// Read [Setting] declarations from any pool.
+pub trait ReadSetting {
+    fn read<T: SettingValue>(&self, setting: &Setting<T>) -> Result<T, ConfigError>;
+}
+
+impl<P: FlatPool + ?Sized> ReadSetting for P {
+    fn read<T: SettingValue>(&self, setting: &Setting<T>) -> Result<T, ConfigError> {
+        setting.read(self)
+    }
+}
+
+/// ## Setting registry
+///
+/// All the settings declared by an application, to generate their documentation from the
+/// [schema](SettingRegistry::schema) and to run strict checks rejecting unknown settings, which
+/// are usually typos.
+///
+/// ```rust
+/// use flat_config::{Setting, SettingRegistry, pool::SimpleFlatPool};
+///
+/// const PORT: Setting<u16> = Setting::new("http.port").default(8080);
+/// const VERBOSE: Setting<bool> = Setting::new("verbose").default(false);
+///
+/// let mut registry = SettingRegistry::default();
+/// registry.register(&PORT).register(&VERBOSE);
+///
+/// let mut pool = SimpleFlatPool::default();
+/// pool.add("http.prot", 80.into());
+///
+/// assert_eq!(vec!["http.prot"], registry.unknown_keys(&pool));
+/// assert!(registry.check_strict(&pool).is_err());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SettingRegistry {
+    schema: Schema,
+    checks: Vec<(&'static str, Check)>,
+}
+
+/// Conversion of a flat value to the type of a setting, the converted value is dropped.
+type Check = fn(&FlatValue) -> Result<(), ConfigError>;
+
+impl SettingRegistry {
+    /// Declare a setting, a setting with the same key is replaced.
+    pub fn register<T: SettingValue>(&mut self, setting: &Setting<T>) -> &mut Self {
+        let check: Check = |value| T::from_flat(value).map(|_| ());
+
+        self.schema.add(setting.schema());
+        self.checks.retain(|(key, _)| *key != setting.key);
+        self.checks.push((setting.key, check));
+
+        self
+    }
+
+    /// Keys of the declared settings in the order they were registered.
+    pub fn keys(&self) -> Vec<&str> {
+        self.schema
+            .settings()
+            .iter()
+            .map(|setting| setting.key.as_str())
+            .collect()
+    }
+
+    /// Schema of the declared settings, to generate their documentation with
+    /// [SchemaDocs](crate::SchemaDocs).
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    /// Keys of the pool that are not declared.
+    pub fn unknown_keys(&self, pool: &(impl FlatPool + ?Sized)) -> Vec<String> {
+        pool.keys()
+            .into_iter()
+            .filter(|key| self.schema.get(key).is_none())
+            .collect()
+    }
+
+    /// Check every declared setting is present or has a default value and converts to its type,
+    /// and the pool holds no unknown setting. All the errors are reported in a
+    /// [ConfigError::Invalid] error.
+    pub fn check_strict(&self, pool: &(impl FlatPool + ?Sized)) -> Result<(), ConfigError> {
+        let mut errors = Vec::new();
+
+        for setting in self.schema.settings() {
+            let check = self
+                .checks
+                .iter()
+                .find(|(key, _)| *key == setting.key)
+                .map(|(_, check)| check);
+
+            if let (Some(check), true) = (check, pool.has(&setting.key)) {
+                if let Err(error) = pool.require(&setting.key).and_then(|value| check(&value)) {
+                    errors.push(error.with_key(&setting.key));
+                }
+            } else if setting.default.is_none() {
+                errors.push(ConfigError::Missing {
+                    field_name: setting.key.clone(),
+                    fields: format!("{pool:?}"),
+                });
+            }
+        }
+        for key in self.unknown_keys(pool) {
+            errors.push(ConfigError::IncorrectValue("unknown setting".to_string()).with_key(&key));
+        }
+
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(ConfigError::Invalid(errors)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{pool::SimpleFlatPool, Choice, SchemaDocs};
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum LogLevel {
+        Info,
+        Debug,
+    }
+
+    impl FlatEnum for LogLevel {
+        fn choices() -> Vec<Choice<Self>> {
+            vec![
+                Choice::new(Self::Info, "info"),
+                Choice::new(Self::Debug, "debug"),
+            ]
+        }
+    }
+
+    const PORT: Setting<u16> = Setting::new("http.port")
+        .description("Port the server listens on.")
+        .default(8080);
+    const ROOT: Setting<PathBuf> = Setting::new("http.root").default_with(|| "/srv".into());
+    const TIMEOUT: Setting<Duration> =
+        Setting::new("http.timeout").default(Duration::from_secs(90));
+    const LEVEL: Setting<LogLevel> = Setting::new("log.level").default(LogLevel::Info);
+    const NAME: Setting<String> = Setting::new("name");
+
+    fn init_registry() -> SettingRegistry {
+        let mut registry = SettingRegistry::default();
+        registry
+            .register(&PORT)
+            .register(&ROOT)
+            .register(&TIMEOUT)
+            .register(&LEVEL)
+            .register(&NAME);
+
+        registry
+    }
+
+    #[test]
+    fn read() {
+        let mut pool = SimpleFlatPool::default();
+        pool.add("log.level", "debug".into())
+            .add("name", "web".into());
+
+        assert_eq!(8080, pool.read(&PORT).unwrap());
+        assert_eq!(PathBuf::from("/srv"), pool.read(&ROOT).unwrap());
+        assert_eq!(Duration::from_secs(90), pool.read(&TIMEOUT).unwrap());
+        assert_eq!(LogLevel::Debug, pool.read(&LEVEL).unwrap());
+        assert_eq!("web", pool.read(&NAME).unwrap());
+    }
+
+    #[test]
+    fn read_errors() {
+        let mut pool = SimpleFlatPool::default();
+        pool.add("http.port", 70000.into());
+
+        assert_eq!(
+            "CONFIGURATION ERROR: Setting 'http.port': Incorrect value: integer 70000 is out of the u16 range.",
+            pool.read(&PORT).unwrap_err().to_string()
+        );
+        assert!(matches!(
+            pool.read(&NAME),
+            Err(ConfigError::Missing { ref field_name, .. }) if field_name == "name"
+        ));
+    }
+
+    #[test]
+    fn registry() {
+        let registry = init_registry();
+
+        assert_eq!(
+            vec![
+                "http.port",
+                "http.root",
+                "http.timeout",
+                "log.level",
+                "name"
+            ],
+            registry.keys()
+        );
+        assert_eq!(
+            Some(&FlatValue::from("90s")),
+            registry
+                .schema()
+                .get("http.timeout")
+                .unwrap()
+                .default
+                .as_ref()
+        );
+        assert!(registry.schema().get("name").unwrap().required);
+
+        let markdown = SchemaDocs::new(registry.schema()).markdown();
+        assert!(markdown.contains(
+            "| `http.port` | integer | `8080` | no | `HTTP__PORT` | `--http.port` | Port the server listens on. |"
+        ));
+    }
+
+    #[test]
+    fn strict() {
+        let registry = init_registry();
+        let mut pool = SimpleFlatPool::default();
+        pool.add("name", "web".into());
+
+        assert!(registry.check_strict(&pool).is_ok());
+
+        pool.add("http.prot", 80.into())
+            .add("log.level", "trace".into());
+        let error = registry.check_strict(&pool).unwrap_err();
+
+        assert_eq!(
+            "CONFIGURATION ERROR: 2 invalid settings:\n  - Setting 'log.level': Incorrect value: invalid value 'trace', accepted values are: 'info', 'debug'.\n  - Setting 'http.prot': Incorrect value: unknown setting.",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn secret_to_flat() {
+        const PASSWORD: Setting<SecretString> = Setting::new("database.password");
+        let mut pool = SimpleFlatPool::default();
+        pool.add_secret(PASSWORD.key(), SecretString::from("hunter2").to_flat());
+
+        assert_eq!(SecretString::from("hunter2"), pool.read(&PASSWORD).unwrap());
+        assert!(!format!("{pool:?}").contains("hunter2"));
+    }
+
+    #[test]
+    fn byte_size_to_flat() {
+        let size = ByteSize::from(1_500_000);
+        let read: ByteSize = size.to_flat().try_unwrap().unwrap();
+
+        assert_eq!(size, read);
+    }
+}
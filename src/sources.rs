@@ -0,0 +1,409 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    path::home_dir,
+    pool::{
+        ArgsFlatPool, EnvFlatPool, FlatPool, IncludeFlatPool, Layer, LayeredFlatPool,
+        SimpleFlatPool,
+    },
+    ConfigError, DropIns,
+};
+
+#[derive(Debug)]
+enum CommandLine {
+    ProcessArgs,
+    Args(Vec<String>),
+    Pool(Layer),
+}
+
+/// ## Configuration sources
+///
+/// The usual stack of configuration sources of an application, from the lowest to the highest
+/// precedence:
+///
+///  1. the compiled defaults,
+///  2. the system file `/etc/<app>/config.toml`,
+///  3. the system [drop-ins](crate::DropIns) `/etc/<app>/conf.d/*`, in lexical order,
+///  4. the user file `$XDG_CONFIG_HOME/<app>/config.toml`, `XDG_CONFIG_HOME` defaulting to
+///     `~/.config`,
+///  5. the local file `./<app>.toml`,
+///  6. the environment variables starting with `<APP>_`, the application name upper cased with
+///     other characters than letters and digits replaced by `_`, named by
+///     [env_var_name](crate::env_var_name) like `<APP>_SERVER__PORT`,
+///  7. the command line flags of the process, read by an [ArgsFlatPool].
+///
+/// Each step can be changed or disabled. Missing files and directories are skipped, files that
+/// cannot be read or parsed are errors. The format of a file is guessed from its extension and
+/// its [includes](crate::pool::IncludeFlatPool) are read.
+///
+/// ```rust,no_run
+/// use flat_config::{ConfigSources, pool::{FlatPool, SimpleFlatPool}};
+///
+/// let mut defaults = SimpleFlatPool::default();
+/// defaults.add("server.port", 8080.into());
+///
+/// let pool = ConfigSources::new("my-app")
+///     .defaults(defaults)
+///     .no_system_file()
+///     .args(["--server.port=9090"])
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(Some(9090.into()), pool.get("server.port"));
+/// ```
+#[derive(Debug)]
+pub struct ConfigSources {
+    defaults: Option<Layer>,
+    system_file: Option<PathBuf>,
+    drop_in_dir: Option<PathBuf>,
+    user_file: Option<PathBuf>,
+    local_file: Option<PathBuf>,
+    env_prefix: Option<String>,
+    env_vars: Option<Vec<(String, String)>>,
+    command_line: Option<CommandLine>,
+}
+
+/// `$XDG_CONFIG_HOME`, relative paths are ignored as required by the XDG specification.
+fn user_config_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| home_dir().map(|home| home.join(".config")))
+}
+
+impl ConfigSources {
+    /// Standard sources of the given application.
+    pub fn new(app: &str) -> Self {
+        let prefix: String = app
+            .chars()
+            .map(|c| match c.is_ascii_alphanumeric() {
+                true => c.to_ascii_uppercase(),
+                false => '_',
+            })
+            .collect();
+
+        Self {
+            defaults: None,
+            system_file: Some(Path::new("/etc").join(app).join("config.toml")),
+            drop_in_dir: Some(Path::new("/etc").join(app).join("conf.d")),
+            user_file: user_config_dir().map(|dir| dir.join(app).join("config.toml")),
+            local_file: Some(PathBuf::from(format!("{app}.toml"))),
+            env_prefix: Some(format!("{prefix}_")),
+            env_vars: None,
+            command_line: Some(CommandLine::ProcessArgs),
+        }
+    }
+
+    /// Compiled default values, like the [defaults](crate::Schema::defaults) of a schema.
+    pub fn defaults(mut self, pool: impl FlatPool + Send + Sync + 'static) -> Self {
+        self.defaults = Some(Box::new(pool));
+
+        self
+    }
+
+    pub fn system_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.system_file = Some(path.into());
+
+        self
+    }
+
+    pub fn no_system_file(mut self) -> Self {
+        self.system_file = None;
+
+        self
+    }
+
+    /// Directory of the drop-in files overriding the system file.
+    pub fn drop_in_dir(mut self, path: impl Into<PathBuf>) -> Self {
+        self.drop_in_dir = Some(path.into());
+
+        self
+    }
+
+    pub fn no_drop_in_dir(mut self) -> Self {
+        self.drop_in_dir = None;
+
+        self
+    }
+
+    pub fn user_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.user_file = Some(path.into());
+
+        self
+    }
+
+    pub fn no_user_file(mut self) -> Self {
+        self.user_file = None;
+
+        self
+    }
+
+    pub fn local_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.local_file = Some(path.into());
+
+        self
+    }
+
+    pub fn no_local_file(mut self) -> Self {
+        self.local_file = None;
+
+        self
+    }
+
+    /// Prefix of the environment variables, `_` included.
+    pub fn env_prefix(mut self, prefix: &str) -> Self {
+        self.env_prefix = Some(prefix.to_string());
+
+        self
+    }
+
+    /// Read the environment settings from the given variables instead of the process ones.
+    pub fn env_vars<K, V>(mut self, vars: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let vars = vars
+            .into_iter()
+            .map(|(name, value)| (name.as_ref().to_string(), value.as_ref().to_string()));
+        self.env_vars = Some(vars.collect());
+
+        self
+    }
+
+    pub fn no_env(mut self) -> Self {
+        self.env_prefix = None;
+
+        self
+    }
+
+    /// Read the command line flags from the given arguments instead of the process ones.
+    pub fn args(mut self, args: impl IntoIterator<Item = impl AsRef<str>>) -> Self {
+        let args = args.into_iter().map(|arg| arg.as_ref().to_string());
+        self.command_line = Some(CommandLine::Args(args.collect()));
+
+        self
+    }
+
+    /// Use the given pool as the command line settings, typically built from the arguments parsed
+    /// by a dedicated library.
+    pub fn command_line(mut self, pool: impl FlatPool + Send + Sync + 'static) -> Self {
+        self.command_line = Some(CommandLine::Pool(Box::new(pool)));
+
+        self
+    }
+
+    pub fn no_command_line(mut self) -> Self {
+        self.command_line = None;
+
+        self
+    }
+
+    /// Configuration files of the enabled steps, whether they exist or not, drop-ins excluded.
+    pub fn files(&self) -> Vec<&Path> {
+        [&self.system_file, &self.user_file, &self.local_file]
+            .into_iter()
+            .flatten()
+            .map(PathBuf::as_path)
+            .collect()
+    }
+
+    /// Load the enabled sources into a layered pool.
+    pub fn build(self) -> Result<LayeredFlatPool, ConfigError> {
+        let mut layers: Vec<Layer> = Vec::new();
+
+        layers.push(
+            self.defaults
+                .unwrap_or_else(|| Box::new(SimpleFlatPool::default())),
+        );
+
+        let drop_ins = match &self.drop_in_dir {
+            Some(dir) => DropIns::new(dir).files()?,
+            None => Vec::new(),
+        };
+        let files = self
+            .system_file
+            .into_iter()
+            .chain(drop_ins)
+            .chain(self.user_file)
+            .chain(self.local_file);
+
+        for path in files {
+            if path.exists() {
+                layers.push(Box::new(IncludeFlatPool::load(path)?));
+            }
+        }
+
+        match (self.env_prefix, self.env_vars) {
+            (Some(prefix), Some(vars)) => {
+                layers.push(Box::new(EnvFlatPool::from_vars(&prefix, vars)))
+            }
+            (Some(prefix), None) => layers.push(Box::new(EnvFlatPool::new(&prefix))),
+            (None, _) => {}
+        }
+
+        match self.command_line {
+            Some(CommandLine::ProcessArgs) => layers.push(Box::new(ArgsFlatPool::from_env())),
+            Some(CommandLine::Args(args)) => layers.push(Box::new(ArgsFlatPool::parse(args))),
+            Some(CommandLine::Pool(pool)) => layers.push(pool),
+            None => {}
+        }
+
+        Ok(LayeredFlatPool::new(layers))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::{ReadSetting, Setting, SettingRegistry};
+
+    fn write_file(name: &str, content: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("flat_config_sources_{}", std::process::id()));
+        let path = dir.join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, content).unwrap();
+
+        path
+    }
+
+    #[test]
+    fn default_steps() {
+        let sources = ConfigSources::new("my-app");
+
+        assert_eq!(Some("MY_APP_"), sources.env_prefix.as_deref());
+        assert_eq!(Path::new("/etc/my-app/config.toml"), sources.files()[0]);
+        assert_eq!(Some(&Path::new("my-app.toml")), sources.files().last());
+    }
+
+    /// Settings spread over a file, the environment and the command line.
+    fn server_stack() -> LayeredFlatPool {
+        let system = write_file("server.ini", "[server]\nhost = localhost\nport = 80\n");
+
+        ConfigSources::new("app")
+            .system_file(&system)
+            .no_drop_in_dir()
+            .no_user_file()
+            .no_local_file()
+            .env_vars([("APP_SERVER__PORT", "9090"), ("HOME", "/root")])
+            .args(["--server.dry-run"])
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn strict_settings() {
+        const HOST: Setting<String> = Setting::new("server.host");
+        const PORT: Setting<u16> = Setting::new("server.port");
+        const DRY_RUN: Setting<bool> = Setting::new("server.dry_run").default(false);
+
+        let pool = server_stack();
+        let mut registry = SettingRegistry::default();
+        registry.register(&HOST).register(&PORT).register(&DRY_RUN);
+
+        registry.check_strict(&pool).unwrap();
+        assert_eq!(
+            vec!["server.dry_run", "server.host", "server.port"],
+            pool.keys()
+        );
+        assert_eq!(9090, pool.read(&PORT).unwrap());
+        assert!(pool.read(&DRY_RUN).unwrap());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize() {
+        #[derive(serde::Deserialize)]
+        struct Server {
+            host: String,
+            port: u16,
+            dry_run: bool,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct App {
+            server: Server,
+        }
+
+        let app: App = crate::from_pool(&server_stack()).unwrap();
+
+        assert_eq!("localhost", app.server.host);
+        assert_eq!(9090, app.server.port);
+        assert!(app.server.dry_run);
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn precedence() {
+        let system = write_file("system.toml", "a = 1\nb = 1\nc = 1\nd = 1\n");
+        let drop_in = write_file("conf.d/10-site.toml", "a = 10\nb = 10\n");
+        let user = write_file("user.toml", "b = 2\nc = 2\nd = 2\n");
+        let local = write_file("local.ini", "c = 3\nd = 3\n");
+        let mut defaults = SimpleFlatPool::default();
+        defaults.add("name", "default".into()).add("z", 0.into());
+
+        let pool = ConfigSources::new("app")
+            .defaults(defaults)
+            .system_file(&system)
+            .drop_in_dir(drop_in.parent().unwrap())
+            .user_file(&user)
+            .local_file(&local)
+            .env_vars([("APP_NAME", "env"), ("NAME", "ignored")])
+            .args(["--d=4"])
+            .build()
+            .unwrap();
+
+        for (key, value) in [("a", 10), ("b", 2), ("c", 3), ("d", 4), ("z", 0)] {
+            assert_eq!(Some(value.into()), pool.get(key), "{key}");
+        }
+        assert_eq!(Some("env".into()), pool.get("name"));
+        assert_eq!(vec![system, drop_in, user, local], pool.source_files());
+    }
+
+    #[test]
+    fn skipped_steps() {
+        let pool = ConfigSources::new("app")
+            .system_file("/unexistent/app/config.toml")
+            .drop_in_dir("/unexistent/app/conf.d")
+            .no_user_file()
+            .no_local_file()
+            .no_env()
+            .no_command_line()
+            .build()
+            .unwrap();
+
+        assert!(pool.keys().is_empty());
+
+        let mut command_line = SimpleFlatPool::default();
+        command_line.add("verbose", true.into());
+        let pool = ConfigSources::new("app")
+            .no_system_file()
+            .no_drop_in_dir()
+            .no_user_file()
+            .no_local_file()
+            .no_env()
+            .command_line(command_line)
+            .build()
+            .unwrap();
+
+        assert_eq!(vec!["verbose"], pool.keys());
+    }
+
+    #[test]
+    fn broken_file() {
+        let broken = write_file("broken.toml", "broken");
+        let error = ConfigSources::new("app")
+            .system_file(&broken)
+            .no_drop_in_dir()
+            .no_user_file()
+            .no_local_file()
+            .no_env()
+            .no_command_line()
+            .build()
+            .unwrap_err();
+
+        assert!(matches!(error, ConfigError::Source { .. }));
+    }
+}
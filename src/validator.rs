@@ -0,0 +1,346 @@
+use std::{fmt::Display, path::Path};
+
+use crate::{pool::FlatPool, ConfigError, FlatValue, REDACTED};
+
+/// ## Validators
+///
+/// Reusable rules a setting value must satisfy. Validators are attached to settings with
+/// [SettingSchema::validator](crate::SettingSchema::validator) or run directly against a pool.
+/// A broken rule produces a [ConfigError::Rule] error naming the setting, the rule and the value
+/// (redacted for secret settings).
+///
+///  * `Range`: integer between the bounds, inclusive,
+///  * `Length`: number of characters of a text between the bounds, inclusive,
+///  * `Matches`: text matching a regular expression (requires the `regex` feature),
+///  * `NotEmpty`: text that is not empty or blank,
+///  * `OneOf`: one of the given values,
+///  * `PathExists`: text naming an existing path,
+///  * `UrlScheme`: URL text using one of the given schemes.
+///
+/// ```rust
+/// use flat_config::{Validator, pool::SimpleFlatPool};
+///
+/// let mut pool = SimpleFlatPool::default();
+/// pool.add("port", 0.into());
+/// let error = Validator::range(1, 65535).check(&pool, "port").unwrap_err();
+///
+/// assert_eq!(
+///     "CONFIGURATION ERROR: Setting 'port': Value 0 does not satisfy the rule range(1, 65535).",
+///     error.to_string()
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub enum Validator {
+    Range {
+        min: Option<isize>,
+        max: Option<isize>,
+    },
+    Length {
+        min: Option<usize>,
+        max: Option<usize>,
+    },
+    #[cfg(feature = "regex")]
+    Matches(regex::Regex),
+    NotEmpty,
+    OneOf(Vec<FlatValue>),
+    PathExists,
+    UrlScheme(Vec<String>),
+}
+
+impl Validator {
+    /// Integer between `min` and `max` inclusive.
+    pub fn range(min: isize, max: isize) -> Self {
+        Self::Range {
+            min: Some(min),
+            max: Some(max),
+        }
+    }
+
+    /// Integer greater than or equal to `min`.
+    pub fn min(min: isize) -> Self {
+        Self::Range {
+            min: Some(min),
+            max: None,
+        }
+    }
+
+    /// Integer lower than or equal to `max`.
+    pub fn max(max: isize) -> Self {
+        Self::Range {
+            min: None,
+            max: Some(max),
+        }
+    }
+
+    /// Text holding between `min` and `max` characters inclusive.
+    pub fn length(min: usize, max: usize) -> Self {
+        Self::Length {
+            min: Some(min),
+            max: Some(max),
+        }
+    }
+
+    /// Text holding at least `min` characters.
+    pub fn min_length(min: usize) -> Self {
+        Self::Length {
+            min: Some(min),
+            max: None,
+        }
+    }
+
+    /// Text holding at most `max` characters.
+    pub fn max_length(max: usize) -> Self {
+        Self::Length {
+            min: None,
+            max: Some(max),
+        }
+    }
+
+    /// Text matching the given regular expression, the error tells why the pattern is invalid.
+    #[cfg(feature = "regex")]
+    pub fn matches(pattern: &str) -> Result<Self, ConfigError> {
+        regex::Regex::new(pattern)
+            .map(Self::Matches)
+            .map_err(|e| ConfigError::IncorrectValue(format!("invalid pattern '{pattern}': {e}")))
+    }
+
+    /// Text that is not empty or blank.
+    pub fn not_empty() -> Self {
+        Self::NotEmpty
+    }
+
+    /// One of the given values.
+    pub fn one_of(values: Vec<FlatValue>) -> Self {
+        Self::OneOf(values)
+    }
+
+    /// Text naming an existing file or directory.
+    pub fn path_exists() -> Self {
+        Self::PathExists
+    }
+
+    /// URL whose scheme is one of the given ones, compared case insensitively.
+    pub fn url_scheme(schemes: &[&str]) -> Self {
+        Self::UrlScheme(schemes.iter().map(|s| s.to_ascii_lowercase()).collect())
+    }
+
+    /// Check the value of the given setting, an absent setting is valid.
+    pub fn check(&self, pool: &(impl FlatPool + ?Sized), key: &str) -> Result<(), ConfigError> {
+        if !pool.has(key) {
+            return Ok(());
+        }
+
+        let value = pool.require(key)?;
+
+        match self.accepts(&value) {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(ConfigError::Rule {
+                rule: self.to_string(),
+                value: match pool.is_secret(key) {
+                    true => REDACTED.to_string(),
+                    false => value.literal(),
+                },
+            }
+            .with_key(key)),
+            Err(error) => Err(error.with_key(key)),
+        }
+    }
+
+    /// Does the value satisfy the rule? Values of the wrong type are errors.
+    fn accepts(&self, value: &FlatValue) -> Result<bool, ConfigError> {
+        let mismatch = |expected: &str| ConfigError::TypeMismatch {
+            expected: expected.to_string(),
+            present: value.display(),
+        };
+
+        match (self, value) {
+            (Self::OneOf(values), value) => Ok(values.contains(value)),
+            (Self::Range { min, max }, FlatValue::Integer(i)) => {
+                Ok(min.is_none_or(|min| *i >= min) && max.is_none_or(|max| *i <= max))
+            }
+            (Self::Range { .. }, _) => Err(mismatch("integer")),
+            (_, FlatValue::Text(text)) => Ok(self.accepts_text(text)),
+            _ => Err(mismatch("text")),
+        }
+    }
+
+    fn accepts_text(&self, text: &str) -> bool {
+        match self {
+            Self::Length { min, max } => {
+                let length = text.chars().count();
+
+                min.is_none_or(|min| length >= min) && max.is_none_or(|max| length <= max)
+            }
+            #[cfg(feature = "regex")]
+            Self::Matches(regex) => regex.is_match(text),
+            Self::NotEmpty => !text.trim().is_empty(),
+            Self::PathExists => Path::new(text).exists(),
+            Self::UrlScheme(schemes) => match text.split_once("://") {
+                Some((scheme, rest)) => {
+                    !rest.is_empty() && schemes.contains(&scheme.to_ascii_lowercase())
+                }
+                None => false,
+            },
+            Self::Range { .. } | Self::OneOf(_) => false,
+        }
+    }
+}
+
+impl PartialEq for Validator {
+    fn eq(&self, other: &Self) -> bool {
+        // Regular expressions are equal when their patterns are.
+        self.to_string() == other.to_string()
+    }
+}
+
+/// Rules are named after the functions creating them.
+impl Display for Validator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let list = |values: Vec<String>| values.join(", ");
+
+        match self {
+            Self::Range {
+                min: Some(min),
+                max: Some(max),
+            } => write!(f, "range({min}, {max})"),
+            Self::Range { min: Some(min), .. } => write!(f, "min({min})"),
+            Self::Range { max: Some(max), .. } => write!(f, "max({max})"),
+            Self::Range { .. } => write!(f, "range()"),
+            Self::Length {
+                min: Some(min),
+                max: Some(max),
+            } => write!(f, "length({min}, {max})"),
+            Self::Length { min: Some(min), .. } => write!(f, "min_length({min})"),
+            Self::Length { max: Some(max), .. } => write!(f, "max_length({max})"),
+            Self::Length { .. } => write!(f, "length()"),
+            #[cfg(feature = "regex")]
+            Self::Matches(regex) => write!(f, "matches({:?})", regex.as_str()),
+            Self::NotEmpty => write!(f, "not_empty"),
+            Self::OneOf(values) => write!(
+                f,
+                "one_of({})",
+                list(values.iter().map(FlatValue::literal).collect())
+            ),
+            Self::PathExists => write!(f, "path_exists"),
+            Self::UrlScheme(schemes) => write!(
+                f,
+                "url_scheme({})",
+                list(schemes.iter().map(|s| format!("{s:?}")).collect())
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pool::SimpleFlatPool;
+
+    fn init_pool() -> SimpleFlatPool {
+        let mut pool = SimpleFlatPool::default();
+        pool.add("port", 8080.into())
+            .add("name", "web".into())
+            .add("blank", "  ".into())
+            .add("manifest", env!("CARGO_MANIFEST_DIR").into())
+            .add("url", "HTTPS://example.com/".into())
+            .add("dry_run", true.into())
+            .add_secret("password", "hunter2".into());
+
+        pool
+    }
+
+    fn rule_of(error: ConfigError) -> (String, String, String) {
+        match error {
+            ConfigError::Setting { key, error } => match *error {
+                ConfigError::Rule { rule, value } => (key, rule, value),
+                error => panic!("unexpected error {error:?}"),
+            },
+            error => panic!("unexpected error {error:?}"),
+        }
+    }
+
+    #[test]
+    fn valid() {
+        let pool = init_pool();
+
+        for (validator, key) in [
+            (Validator::range(1, 65535), "port"),
+            (Validator::min(8080), "port"),
+            (Validator::max(8080), "port"),
+            (Validator::length(1, 3), "name"),
+            (Validator::not_empty(), "name"),
+            (Validator::one_of(vec![true.into()]), "dry_run"),
+            (Validator::path_exists(), "manifest"),
+            (Validator::url_scheme(&["http", "https"]), "url"),
+            (Validator::not_empty(), "unexistent"),
+        ] {
+            assert!(validator.check(&pool, key).is_ok(), "{validator} {key}");
+        }
+    }
+
+    #[test]
+    fn invalid() {
+        let pool = init_pool();
+
+        for (validator, key, rule, value) in [
+            (Validator::range(1, 1024), "port", "range(1, 1024)", "8080"),
+            (Validator::max_length(2), "name", "max_length(2)", "\"web\""),
+            (Validator::not_empty(), "blank", "not_empty", "\"  \""),
+            (
+                Validator::one_of(vec!["api".into(), 1.into()]),
+                "name",
+                "one_of(\"api\", 1)",
+                "\"web\"",
+            ),
+            (Validator::path_exists(), "name", "path_exists", "\"web\""),
+            (
+                Validator::url_scheme(&["ftp"]),
+                "url",
+                "url_scheme(\"ftp\")",
+                "\"HTTPS://example.com/\"",
+            ),
+            (
+                Validator::min_length(10),
+                "password",
+                "min_length(10)",
+                REDACTED,
+            ),
+        ] {
+            let error = validator.check(&pool, key).unwrap_err();
+
+            assert_eq!(
+                (key.to_string(), rule.to_string(), value.to_string()),
+                rule_of(error)
+            );
+        }
+    }
+
+    #[test]
+    fn type_mismatch() {
+        let pool = init_pool();
+
+        assert!(matches!(
+            Validator::range(1, 2).check(&pool, "name"),
+            Err(ConfigError::Setting { ref key, .. }) if key == "name"
+        ));
+        assert!(Validator::not_empty().check(&pool, "port").is_err());
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn matches() {
+        let pool = init_pool();
+        let validator = Validator::matches("^[a-z]+$").unwrap();
+
+        assert!(validator.check(&pool, "name").is_ok());
+        assert_eq!(
+            (
+                "url".to_string(),
+                "matches(\"^[a-z]+$\")".to_string(),
+                "\"HTTPS://example.com/\"".to_string()
+            ),
+            rule_of(validator.check(&pool, "url").unwrap_err())
+        );
+        assert!(Validator::matches("(").is_err());
+    }
+}
@@ -0,0 +1,239 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, SystemTime},
+};
+
+/// Modification stamp of a file, `None` when the file does not exist.
+type Stamp = Option<(SystemTime, u64)>;
+
+fn stamps(files: &[PathBuf]) -> BTreeMap<PathBuf, Stamp> {
+    files
+        .iter()
+        .map(|path| {
+            let stamp = fs::metadata(path)
+                .ok()
+                .and_then(|metadata| Some((metadata.modified().ok()?, metadata.len())));
+
+            (path.clone(), stamp)
+        })
+        .collect()
+}
+
+/// ## File watcher
+///
+/// Background thread watching configuration files. The modification time and size of the files
+/// are polled at each interval. With the `notify` feature, file system events wake the thread up
+/// as soon as a file changes, polling remains the fallback when they are not available. The
+/// thread stops when the watcher is dropped, this may wait up to one interval.
+#[derive(Debug)]
+pub struct FileWatcher {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl FileWatcher {
+    /// Watch the files returned by `files` and call `on_change` when one of them changes. The
+    /// list of files is refreshed after each change, the thread stops when `files` returns `None`.
+    pub(crate) fn spawn<F, C>(files: F, interval: Duration, on_change: C) -> Self
+    where
+        F: Fn() -> Option<Vec<PathBuf>> + Send + 'static,
+        C: Fn() + Send + 'static,
+    {
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = stop.clone();
+        let thread = thread::spawn(move || {
+            let Some(mut watched) = files() else {
+                return;
+            };
+            let mut last_stamps = stamps(&watched);
+            let mut notifier = Notifier::new(&watched);
+
+            while !thread_stop.load(Ordering::SeqCst) {
+                notifier.wait(interval);
+
+                if thread_stop.load(Ordering::SeqCst) {
+                    break;
+                }
+
+                let current_stamps = stamps(&watched);
+
+                if current_stamps != last_stamps {
+                    on_change();
+
+                    let Some(files) = files() else {
+                        break;
+                    };
+                    // Keep the stamps seen before the change was reported, so changes made
+                    // while it was handled are reported too.
+                    last_stamps = stamps(&files);
+                    for (path, stamp) in current_stamps {
+                        if let Some(last_stamp) = last_stamps.get_mut(&path) {
+                            *last_stamp = stamp;
+                        }
+                    }
+                    watched = files;
+                    notifier.watch(&watched);
+                }
+            }
+        });
+
+        Self {
+            stop,
+            thread: Some(thread),
+        }
+    }
+}
+
+impl Drop for FileWatcher {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Wait for file system events, or for the given interval when no event source is available.
+enum Notifier {
+    #[cfg(feature = "notify")]
+    Notify(events::Events),
+    Polling,
+}
+
+impl Notifier {
+    fn new(files: &[PathBuf]) -> Self {
+        #[cfg(feature = "notify")]
+        if let Some(mut events) = events::Events::new() {
+            events.watch_parents(files);
+
+            return Self::Notify(events);
+        }
+
+        let _ = files;
+
+        Self::Polling
+    }
+
+    fn watch(&mut self, files: &[PathBuf]) {
+        match self {
+            #[cfg(feature = "notify")]
+            Self::Notify(events) => events.watch_parents(files),
+            Self::Polling => {
+                let _ = files;
+            }
+        }
+    }
+
+    fn wait(&self, timeout: Duration) {
+        match self {
+            #[cfg(feature = "notify")]
+            Self::Notify(events) => events.wait(timeout),
+            Self::Polling => thread::sleep(timeout),
+        }
+    }
+}
+
+#[cfg(feature = "notify")]
+mod events {
+    use std::{
+        collections::BTreeSet,
+        path::{Path, PathBuf},
+        sync::mpsc::{self, Receiver},
+        time::Duration,
+    };
+
+    use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+
+    /// Watcher of the directories holding the configuration files, watching directories catches
+    /// editors replacing files instead of writing them.
+    pub(super) struct Events {
+        watcher: RecommendedWatcher,
+        receiver: Receiver<()>,
+        directories: BTreeSet<PathBuf>,
+    }
+
+    impl Events {
+        pub(super) fn new() -> Option<Self> {
+            let (sender, receiver) = mpsc::channel();
+            let watcher = notify::recommended_watcher(move |_| {
+                let _ = sender.send(());
+            })
+            .ok()?;
+
+            Some(Self {
+                watcher,
+                receiver,
+                directories: BTreeSet::new(),
+            })
+        }
+
+        pub(super) fn watch_parents(&mut self, files: &[PathBuf]) {
+            for file in files {
+                let directory = match file.parent() {
+                    Some(parent) if !parent.as_os_str().is_empty() => parent,
+                    _ => Path::new("."),
+                };
+
+                if self.directories.contains(directory) {
+                    continue;
+                }
+
+                if self
+                    .watcher
+                    .watch(directory, RecursiveMode::NonRecursive)
+                    .is_ok()
+                {
+                    self.directories.insert(directory.to_path_buf());
+                }
+            }
+        }
+
+        /// Wait for events until the timeout expires, pending events are discarded as changes
+        /// are detected by comparing the files' stamps.
+        pub(super) fn wait(&self, timeout: Duration) {
+            if self.receiver.recv_timeout(timeout).is_ok() {
+                while self.receiver.try_recv().is_ok() {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+
+    use super::*;
+
+    #[test]
+    fn detect_changes() {
+        let dir = std::env::temp_dir().join(format!("flat_config_watch_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("watched.conf");
+        fs::write(&path, "a = 1\n").unwrap();
+
+        let (sender, receiver) = mpsc::channel();
+        let files = vec![path.clone()];
+        let watcher = FileWatcher::spawn(
+            move || Some(files.clone()),
+            Duration::from_millis(20),
+            move || sender.send(()).unwrap(),
+        );
+
+        thread::sleep(Duration::from_millis(50));
+        fs::write(&path, "a = 22\n").unwrap();
+        receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+
+        fs::remove_file(&path).unwrap();
+        receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+
+        drop(watcher);
+    }
+}
@@ -73,7 +73,7 @@ fn build() {
         .add("database_dir", "/var/database".into())
         .add("verbose_level", 2.into())
         .add("dry_run", true.into());
-    let config = AppConfigBuilder::default()
+    let config = AppConfigBuilder
         .build(&pool)
         .map_err(|e| format!("{e}"))
         .unwrap();
@@ -94,7 +94,7 @@ fn require() {
     pool.add("verbose_level", 2.into())
         .add("app_name", "Application".into())
         .add("dry_run", true.into());
-    let _config = AppConfigBuilder::default().build(&pool).unwrap();
+    let _config = AppConfigBuilder.build(&pool).unwrap();
 }
 
 #[test]
@@ -104,7 +104,7 @@ fn unwrap() {
     pool.add("verbose_level", 2.into())
         .add("database_dir", "/var/database".into())
         .add("dry_run", true.into());
-    let _config = AppConfigBuilder::default().build(&pool);
+    let _config = AppConfigBuilder.build(&pool);
 }
 
 #[test]
@@ -113,7 +113,7 @@ fn default() {
     pool.add("whatever", "something".into())
         .add("app_name", "Application".into())
         .add("database_dir", "/var/database".into());
-    let config = AppConfigBuilder::default()
+    let config = AppConfigBuilder
         .build(&pool)
         .map_err(|e| format!("{e}"))
         .unwrap();
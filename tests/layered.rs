@@ -56,8 +56,8 @@ fn build_layered_pool() {
         .add("start_epoch", 3.into());
 
     let config_pool = LayeredFlatPool::new(vec![Box::new(default_pool), Box::new(file_pool)]);
-    let config = AppConfigBuilder::default().build(&config_pool).unwrap();
+    let config = AppConfigBuilder.build(&config_pool).unwrap();
 
     assert_eq!("whatever".to_string(), config.app_name);
-    assert_eq!(PathBuf::new().join("/alternate/dir"), config.database_dir);
+    assert_eq!(PathBuf::from("/alternate/dir"), config.database_dir);
 }
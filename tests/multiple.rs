@@ -36,7 +36,7 @@ impl ConfigBuilder<ConfigB> for AppConfigBuilder {
 
 #[test]
 fn both_config() {
-    let builder = AppConfigBuilder::default();
+    let builder = AppConfigBuilder;
     let config_a: ConfigA = builder.build(&SimpleFlatPool::default()).unwrap();
     let config_b: ConfigB = builder.build(&SimpleFlatPool::default()).unwrap();
 